@@ -0,0 +1,278 @@
+use alloc::{format, string::String, string::ToString, vec, vec::Vec};
+use proc_macro::{Diagnostic, Level};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Block, Expr, ExprMatch, Macro, Pat, Path, Stmt};
+
+use crate::{Choice, ChooseInput, MachineMacroInput, is_choose};
+
+///
+/// Check every `match` on the result of a `choose!` in a machine body,
+///  warning about arms for actions which are never offered (unless they
+///  are marked `unreachable!()`), and offered actions which are only
+///  handled by a catch-all arm.
+///
+/// This handles both `match choose![...] { ... }` and
+///  `let x = choose![...];` followed by `match x { ... }` in the same block.
+///
+pub(crate) fn check_choices(body: &Expr) {
+    ChoiceChecker.visit_expr(body);
+}
+
+struct ChoiceChecker;
+
+impl<'ast> Visit<'ast> for ChoiceChecker {
+    fn visit_block(&mut self, block: &'ast Block) {
+        for (i, stmt) in block.stmts.iter().enumerate() {
+            let Stmt::Local(local) = stmt else { continue };
+
+            if let Pat::Ident(binding) = &local.pat
+                && let Some(init) = &local.init
+                && let Expr::Macro(choose) = &*init.expr
+                && is_choose(&choose.mac)
+            {
+                for later in &block.stmts[i + 1..] {
+                    if let Stmt::Expr(Expr::Match(expr_match), _) = later
+                        && let Expr::Path(scrutinee) = &*expr_match.expr
+                        && scrutinee.path.is_ident(&binding.ident)
+                    {
+                        check_match(&choose.mac.tokens, expr_match);
+                    }
+                }
+            }
+        }
+
+        visit::visit_block(self, block);
+    }
+
+    fn visit_expr_match(&mut self, expr_match: &'ast ExprMatch) {
+        if let Expr::Macro(choose) = &*expr_match.expr
+            && is_choose(&choose.mac)
+        {
+            check_match(&choose.mac.tokens, expr_match);
+        }
+
+        visit::visit_expr_match(self, expr_match);
+    }
+}
+
+fn check_match(choices: &proc_macro2::TokenStream, expr_match: &ExprMatch) {
+    let Some(offered) = offered(choices) else {
+        return;
+    };
+
+    let mut handled = Vec::new();
+    let mut catch_all = None;
+
+    for arm in &expr_match.arms {
+        let mut cases = Vec::new();
+        flatten(&arm.pat, &mut cases);
+
+        for case in cases {
+            // Unit variants brought into scope with `use` look like bindings.
+            let name = match case {
+                Pat::Ident(binding)
+                    if offered
+                        .iter()
+                        .any(|offer| same_action(offer, &[binding.ident.to_string()])) =>
+                {
+                    Some(vec![binding.ident.to_string()])
+                }
+                _ => pattern_name(case),
+            };
+
+            match name {
+                Some(name) => {
+                    if !offered.iter().any(|offer| same_action(offer, &name))
+                        && !is_unreachable(&arm.body)
+                    {
+                        Diagnostic::spanned(
+                            case.span().unwrap(),
+                            Level::Warning,
+                            format!(
+                                "`{}` is never offered by the `choose!` matched on here",
+                                name.join("::")
+                            ),
+                        )
+                        .emit();
+                    }
+                    handled.push(name);
+                }
+                None if is_catch_all(case) && arm.guard.is_none() => {
+                    catch_all.get_or_insert(case.span());
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let Some(catch_all) = catch_all {
+        for offer in &offered {
+            if !handled.iter().any(|name| same_action(offer, name)) {
+                Diagnostic::spanned(
+                    catch_all.unwrap(),
+                    Level::Warning,
+                    format!(
+                        "`{}` is offered by `choose!` but only handled by a catch-all arm",
+                        offer.join("::")
+                    ),
+                )
+                .emit();
+            }
+        }
+    }
+}
+
+// The path of an action, one segment per element
+type ActionPath = Vec<String>;
+
+///
+/// Paths of the actions offered by a `choose!`, or `None` if they can't
+///  be known until runtime.
+///
+fn offered(choices: &proc_macro2::TokenStream) -> Option<Vec<ActionPath>> {
+    let ChooseInput(choices) = syn::parse2(choices.clone()).ok()?;
+
+    choices
+        .iter()
+        .map(|choice| match choice {
+            Choice::Always(action) | Choice::When(action, _) => expression_name(action),
+            Choice::Spread(_) => None,
+        })
+        .collect()
+}
+
+fn expression_name(action: &Expr) -> Option<ActionPath> {
+    match action {
+        Expr::Path(path) => Some(segments(&path.path)),
+        Expr::Call(call) => expression_name(&call.func),
+        Expr::Struct(expr_struct) => Some(segments(&expr_struct.path)),
+        _ => None,
+    }
+}
+
+fn pattern_name(pat: &Pat) -> Option<ActionPath> {
+    match pat {
+        Pat::Path(path) => Some(segments(&path.path)),
+        Pat::TupleStruct(tuple_struct) => Some(segments(&tuple_struct.path)),
+        Pat::Struct(pat_struct) => Some(segments(&pat_struct.path)),
+        _ => None,
+    }
+}
+
+///
+/// Whether two paths may name the same action, which is the case when
+///  the shorter one is a suffix of the other, since either of them may
+///  have been imported with `use`.
+///
+fn same_action(a: &[String], b: &[String]) -> bool {
+    let len = a.len().min(b.len());
+    len > 0 && a[a.len() - len..] == b[b.len() - len..]
+}
+
+// Whether an arm was explicitly marked as not reachable.
+fn is_unreachable(body: &Expr) -> bool {
+    match body {
+        Expr::Macro(mac) => mac.mac.path.is_ident("unreachable"),
+        Expr::Block(block) => match block.block.stmts.as_slice() {
+            [Stmt::Expr(expr, _)] => is_unreachable(expr),
+            [Stmt::Macro(mac)] => mac.mac.path.is_ident("unreachable"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn is_catch_all(pat: &Pat) -> bool {
+    match pat {
+        Pat::Wild(_) => true,
+        Pat::Ident(binding) => binding.subpat.is_none(),
+        _ => false,
+    }
+}
+
+fn flatten<'a>(pat: &'a Pat, cases: &mut Vec<&'a Pat>) {
+    match pat {
+        Pat::Or(or) => or.cases.iter().for_each(|case| flatten(case, cases)),
+        Pat::Paren(paren) => flatten(&paren.pat, cases),
+        _ => cases.push(pat),
+    }
+}
+
+// `Self::` is left out, as it may stand for any of the other prefixes.
+fn segments(path: &Path) -> ActionPath {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .skip_while(|segment| segment == "Self")
+        .collect()
+}
+
+///
+/// Collects the actions offered by each `choose!` in a function, looking
+///  inside the bodies of any machine macros it contains.
+///
+#[derive(Default)]
+pub(crate) struct EdgeTable {
+    pub(crate) choices: Vec<Vec<String>>,
+}
+
+impl EdgeTable {
+    fn visit_macro_tokens(&mut self, mac: &Macro) {
+        if is_choose(mac) {
+            let Ok(ChooseInput(choices)) = syn::parse2(mac.tokens.clone()) else {
+                return;
+            };
+
+            self.choices.push(
+                choices
+                    .iter()
+                    .map(|choice| match choice {
+                        Choice::Always(action) => pretty(quote!(#action)),
+                        Choice::When(action, condition) => pretty(quote!(#action if #condition)),
+                        Choice::Spread(actions) => pretty(quote!(..#actions)),
+                    })
+                    .collect(),
+            );
+        } else if ["machine", "machine_chain", "async_machine"]
+            .iter()
+            .any(|name| mac.path.is_ident(name))
+            && let Ok(MachineMacroInput { body, .. }) = syn::parse2(mac.tokens.clone())
+        {
+            self.visit_expr(&body);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for EdgeTable {
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        self.visit_macro_tokens(mac);
+    }
+}
+
+// Render tokens roughly the way they would be written by hand.
+fn pretty(tokens: proc_macro2::TokenStream) -> String {
+    let mut result = tokens
+        .to_string()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    for (from, to) in [
+        (" :: ", "::"),
+        (":: ", "::"),
+        (" ,", ","),
+        (" . ", "."),
+        (" (", "("),
+        ("( ", "("),
+        (" )", ")"),
+        (" [", "["),
+        ("[ ", "["),
+        (" ]", "]"),
+        (".. ", ".."),
+        (" ..", ".."),
+    ] {
+        result = result.replace(from, to);
+    }
+    result
+}
@@ -0,0 +1,225 @@
+use alloc::vec::Vec;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{Data, DeriveInput, Expr, Fields, Ident, Path, Token, Type, parse_quote};
+
+// `#[machine(transition = Type)]`
+struct MachineAttr {
+    transition: Type,
+}
+
+impl Parse for MachineAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name: Ident = input.parse()?;
+        if name != "transition" {
+            return Err(syn::Error::new(
+                name.span(),
+                "expected `transition = <type>`",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+
+        Ok(MachineAttr {
+            transition: input.parse()?,
+        })
+    }
+}
+
+// `#[on(Edge => Target, guard = f, action = g)]`
+struct OnAttr {
+    edge: Expr,
+    target: Option<Expr>,
+    guard: Option<Path>,
+    action: Option<Path>,
+}
+
+impl Parse for OnAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let edge = input.parse()?;
+
+        let target = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        let mut guard = None;
+        let mut action = None;
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if name == "guard" {
+                guard = Some(input.parse()?);
+            } else if name == "action" {
+                action = Some(input.parse()?);
+            } else {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "expected `guard = <fn>` or `action = <fn>`",
+                ));
+            }
+        }
+
+        Ok(OnAttr {
+            edge,
+            target,
+            guard,
+            action,
+        })
+    }
+}
+
+// Allow writing `Coin` for `Transition::Coin`, and `Paid` for `Self::Paid`.
+fn qualify(expr: Expr, prefix: TokenStream) -> Expr {
+    match &expr {
+        Expr::Path(path) if path.qself.is_none() && path.path.get_ident().is_some() => {
+            parse_quote! { #prefix::#path }
+        }
+        Expr::Call(call) if matches!(&*call.func, Expr::Path(path) if path.path.get_ident().is_some()) =>
+        {
+            let func = &call.func;
+            let args = &call.args;
+            parse_quote! { #prefix::#func(#args) }
+        }
+        Expr::Struct(expr_struct) if expr_struct.path.get_ident().is_some() => {
+            let path = &expr_struct.path;
+            let fields = &expr_struct.fields;
+            let rest = expr_struct.rest.as_ref().map(|rest| quote! { , ..#rest });
+            parse_quote! { #prefix::#path { #fields #rest } }
+        }
+        _ => expr,
+    }
+}
+
+pub(crate) fn derive_machine(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "derive(Machine) is only supported for enums",
+        ));
+    };
+
+    let MachineAttr { transition } = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("machine"))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                &input.ident,
+                "derive(Machine) requires a `#[machine(transition = <type>)]` attribute",
+            )
+        })?
+        .parse_args()?;
+
+    let mut states = Vec::new();
+    for variant in &data.variants {
+        let variant_name = &variant.ident;
+        let pattern = match &variant.fields {
+            Fields::Unit => quote! { #name::#variant_name },
+            Fields::Unnamed(_) => quote! { #name::#variant_name(..) },
+            Fields::Named(_) => quote! { #name::#variant_name { .. } },
+        };
+
+        let transitions = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("on"))
+            .map(|attr| attr.parse_args::<OnAttr>())
+            .collect::<Result<Vec<_>>>()?;
+
+        states.push((pattern, transitions));
+    }
+
+    // Every state offers an array of the same width, so states with fewer
+    //  transitions are padded with `None`.
+    let width = states
+        .iter()
+        .map(|(_, transitions)| transitions.len())
+        .max()
+        .unwrap_or(0);
+
+    let edges = states.iter().map(|(pattern, transitions)| {
+        let offered = transitions.iter().map(|on| {
+            let edge = qualify(on.edge.clone(), quote! { #transition });
+            match &on.guard {
+                Some(guard) => quote! {
+                    if #guard(self) {
+                        ::core::option::Option::Some(#edge)
+                    } else {
+                        ::core::option::Option::None
+                    }
+                },
+                None => quote! { ::core::option::Option::Some(#edge) },
+            }
+        });
+        let padding = (transitions.len()..width).map(|_| quote! { ::core::option::Option::None });
+
+        quote! { #pattern => [#(#offered,)* #(#padding),*] }
+    });
+
+    let traversals = states.iter().map(|(pattern, transitions)| {
+        let taken = transitions.iter().map(|on| {
+            let edge = qualify(on.edge.clone(), quote! { #transition });
+            let guard = on.guard.as_ref().map(|guard| quote! { && #guard(self) });
+            let target = on.target.clone().map(|target| {
+                let target = qualify(target, quote! { #name });
+                quote! { *self = #target; }
+            });
+            let action = on
+                .action
+                .as_ref()
+                .map(|action| quote! { #action(self, edge); });
+
+            quote! {
+                if *edge == #edge #guard {
+                    #target
+                    #action
+                    return;
+                }
+            }
+        });
+
+        quote! {
+            #pattern => {
+                #(#taken)*
+            }
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics enstate::machine::Machine<#name #ty_generics> for #name #ty_generics
+            #where_clause
+        {
+            type Transition = #transition;
+
+            fn edges(&self) -> impl ::core::iter::Iterator<Item = #transition> {
+                let edges: [::core::option::Option<#transition>; #width] = match self {
+                    #(#edges,)*
+                };
+
+                edges.into_iter().flatten()
+            }
+
+            fn state(&mut self) -> #name #ty_generics {
+                ::core::clone::Clone::clone(self)
+            }
+
+            fn traverse(&mut self, edge: &#transition) {
+                match self {
+                    #(#traversals)*
+                }
+            }
+        }
+    })
+}
@@ -1,36 +1,366 @@
-#![feature(coroutines, stmt_expr_attributes, coroutine_trait, trait_alias)]
+#![feature(stmt_expr_attributes, proc_macro_diagnostic)]
 #![no_std]
 
+extern crate alloc;
+
+mod analysis;
+mod derive;
+
+use alloc::{format, string::ToString, vec, vec::Vec};
 use proc_macro::TokenStream;
+use proc_macro2::Span;
 use quote::quote;
 use syn::parse::Result;
 use syn::parse::{Parse, ParseStream};
-use syn::{Expr, Ident, LocalInit, Stmt, Token, parse_macro_input, parse_quote};
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    Expr, ExprMacro, Ident, Item, ItemFn, Macro, Member, Path, Stmt, StmtMacro, Token, UnOp,
+    parse_macro_input, parse_quote,
+};
+
+// The state variables declared by a `machine!`
+enum StateVars {
+    // `count, 0`
+    Single(Ident, Expr),
+    // `count: 0, step: 1`
+    Tuple(Vec<(Ident, Expr)>),
+    // `Form { count: 0, step: 1 }`
+    Struct(Path, Vec<(Ident, Expr)>),
+}
+
+impl StateVars {
+    fn fields(&self) -> Vec<(&Ident, &Expr)> {
+        match self {
+            StateVars::Single(var, init) => vec![(var, init)],
+            StateVars::Tuple(fields) | StateVars::Struct(_, fields) => {
+                fields.iter().map(|(var, init)| (var, init)).collect()
+            }
+        }
+    }
+
+    // The value yielded as the state of the machine at each `choose!`
+    fn yielded(&self) -> Expr {
+        let cloned = |var: &Ident| quote! { ::core::clone::Clone::clone(&#var) };
+
+        match self {
+            StateVars::Single(var, _) => {
+                let var = cloned(var);
+                parse_quote! { #var }
+            }
+            StateVars::Tuple(fields) => {
+                let vars = fields.iter().map(|(var, _)| cloned(var));
+                parse_quote! { (#(#vars,)*) }
+            }
+            StateVars::Struct(path, fields) => {
+                let names = fields.iter().map(|(var, _)| var);
+                let vars = fields.iter().map(|(var, _)| cloned(var));
+                parse_quote! { #path { #(#names: #vars),* } }
+            }
+        }
+    }
+}
 
 // Structure to parse the macro input
 struct MachineMacroInput {
-    state_var: Ident,
-    initial_value: Expr,
+    vars: Option<StateVars>,
+    state: Option<Expr>,
     body: Expr,
 }
 
 // Parser implementation
 impl Parse for MachineMacroInput {
     fn parse(input: ParseStream) -> Result<Self> {
-        let state_var = input.parse()?;
-        input.parse::<Token![,]>()?;
-        let initial_value = input.parse()?;
-        input.parse::<Token![,]>()?;
+        // State variables are optional, and are followed by either `state = ...` or the closure.
+        let closure_or_state = input.peek(Token![|])
+            || input.peek(Token![||])
+            || input.peek(Token![move])
+            || (input.peek(Ident) && input.peek2(Token![=]));
+
+        let vars = if closure_or_state {
+            None
+        } else {
+            Some(input.call(StateVars::parse)?)
+        };
+
+        let state = if input.peek(Ident) && input.peek2(Token![=]) {
+            let name: Ident = input.parse()?;
+            if name != "state" {
+                return Err(syn::Error::new(name.span(), "expected `state = ...`"));
+            }
+            input.parse::<Token![=]>()?;
+            let state = input.parse()?;
+            input.parse::<Token![,]>()?;
+            Some(state)
+        } else {
+            None
+        };
 
         let closure = syn::ExprClosure::parse(input)?;
 
         let body = *closure.body;
 
-        Ok(MachineMacroInput {
-            state_var,
-            initial_value,
-            body,
-        })
+        Ok(MachineMacroInput { vars, state, body })
+    }
+}
+
+impl Parse for StateVars {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let vars = if input.peek(Ident) && input.peek2(Token![,]) {
+            let state_var = input.parse()?;
+            input.parse::<Token![,]>()?;
+            let initial_value = input.parse()?;
+            input.parse::<Token![,]>()?;
+            StateVars::Single(state_var, initial_value)
+        } else if input.peek(Ident) && input.peek2(Token![:]) && !input.peek2(Token![::]) {
+            let mut fields = Vec::new();
+            while input.peek(Ident) && input.peek2(Token![:]) {
+                let var = input.parse()?;
+                input.parse::<Token![:]>()?;
+                fields.push((var, input.parse()?));
+                input.parse::<Token![,]>()?;
+            }
+            StateVars::Tuple(fields)
+        } else {
+            let state: syn::ExprStruct = input.parse()?;
+            input.parse::<Token![,]>()?;
+
+            if let Some(dot2) = state.dot2_token {
+                let rest = &state.rest;
+                return Err(syn::Error::new_spanned(
+                    quote! { #dot2 #rest },
+                    "machine! state structs must list every field",
+                ));
+            }
+
+            let fields = state
+                .fields
+                .into_iter()
+                .map(|field| match field.member {
+                    Member::Named(var) => Ok((var, field.expr)),
+                    Member::Unnamed(index) => Err(syn::Error::new(
+                        index.span,
+                        "machine! state structs must have named fields",
+                    )),
+                })
+                .collect::<Result<_>>()?;
+
+            StateVars::Struct(state.path, fields)
+        };
+
+        Ok(vars)
+    }
+}
+
+// A single entry of a `choose!` list
+enum Choice {
+    Always(Expr),
+    When(Expr, Expr),
+    Spread(Expr),
+}
+
+impl Parse for Choice {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![..]) {
+            input.parse::<Token![..]>()?;
+            return Ok(Choice::Spread(input.parse()?));
+        }
+
+        let action = input.parse()?;
+
+        if input.peek(Token![if]) {
+            input.parse::<Token![if]>()?;
+            return Ok(Choice::When(action, input.parse()?));
+        }
+
+        Ok(Choice::Always(action))
+    }
+}
+
+///
+/// Build the expression for the set of choices offered by a `choose!`.
+///
+/// A list of unconditional, constant choices is promoted to a `'static`
+///  slice, while lists with runtime values, conditional
+///  (`action if condition`) or spread (`..iterator`) entries are collected
+///  into a buffer when the `choose!` is reached.
+///
+fn choices(tokens: proc_macro2::TokenStream) -> Expr {
+    let choices = match syn::parse2::<ChooseInput>(tokens) {
+        Ok(ChooseInput(choices)) => choices,
+        Err(error) => {
+            let error = error.to_compile_error();
+            return parse_quote! { { #error } };
+        }
+    };
+
+    if choices
+        .iter()
+        .all(|choice| matches!(choice, Choice::Always(action) if is_constant(action)))
+    {
+        let actions = choices.iter().map(|choice| match choice {
+            Choice::Always(action) => action,
+            _ => unreachable!(),
+        });
+
+        return parse_quote! {
+            enstate::coroutines::choices::Choices::Static([#(#actions),*].as_slice())
+        };
+    }
+
+    let pushes = choices.iter().map(|choice| match choice {
+        Choice::Always(action) => quote! { __choices.push(#action); },
+        Choice::When(action, condition) => quote! {
+            if #condition {
+                __choices.push(#action);
+            }
+        },
+        Choice::Spread(actions) => quote! { __choices.extend(#actions); },
+    });
+
+    parse_quote! {
+        {
+            let mut __choices = enstate::coroutines::choices::Choices::new();
+            #(#pushes)*
+            __choices
+        }
+    }
+}
+
+///
+/// Whether an action can be promoted to a `'static` slice, judging by its
+///  syntax: literals, and paths to variants or constants, which are named
+///  in upper camel or screaming snake case, possibly applied to constant
+///  arguments.
+///
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(_) => true,
+        Expr::Path(path) => {
+            path.qself.is_none()
+                && path.path.segments.last().is_some_and(|segment| {
+                    segment
+                        .ident
+                        .to_string()
+                        .starts_with(|c: char| c.is_ascii_uppercase())
+                })
+        }
+        Expr::Call(call) => is_constant(&call.func) && call.args.iter().all(is_constant),
+        Expr::Paren(paren) => is_constant(&paren.expr),
+        Expr::Group(group) => is_constant(&group.expr),
+        Expr::Unary(unary) => matches!(unary.op, UnOp::Neg(_)) && is_constant(&unary.expr),
+        _ => false,
+    }
+}
+
+fn is_choose(mac: &Macro) -> bool {
+    mac.path.is_ident("choose")
+}
+
+struct ChooseInput(Punctuated<Choice, Token![,]>);
+
+impl Parse for ChooseInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(ChooseInput(Punctuated::parse_terminated(input)?))
+    }
+}
+
+// Rewrites every `choose!` in a machine body into a `yield`
+struct ChooseVisitor<'a> {
+    // The state to yield alongside the choices, if any.
+    state: Option<&'a Expr>,
+    // Whether to await the choice from an async body rather than yielding it.
+    asynchronous: bool,
+}
+
+impl ChooseVisitor<'_> {
+    fn rewrite(&self, mac: &Macro) -> Expr {
+        let choices = choices(mac.tokens.clone());
+        match self.state {
+            Some(state) if self.asynchronous => parse_quote! {
+                __chooser.choose(#state, #choices).await
+            },
+            Some(state) => parse_quote! {
+                enstate::coroutines::resumed(yield (#state, #choices))
+            },
+            None => parse_quote! {
+                enstate::coroutines::resumed(yield #choices)
+            },
+        }
+    }
+}
+
+impl VisitMut for ChooseVisitor<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Macro(ExprMacro { mac, .. }) if is_choose(mac) => {
+                *expr = self.rewrite(mac);
+            }
+            Expr::Closure(closure)
+                if closure.attrs.iter().any(|a| a.path().is_ident("coroutine")) =>
+            {
+                NoChooseVisitor("a nested coroutine").visit_expr_mut(expr)
+            }
+            Expr::Closure(_) => NoChooseVisitor("a closure").visit_expr_mut(expr),
+            Expr::Async(_) => NoChooseVisitor("an async block").visit_expr_mut(expr),
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::Macro(StmtMacro {
+                mac, semi_token, ..
+            }) if is_choose(mac) => {
+                *stmt = Stmt::Expr(self.rewrite(mac), *semi_token);
+            }
+            _ => visit_mut::visit_stmt_mut(self, stmt),
+        }
+    }
+
+    fn visit_item_mut(&mut self, item: &mut Item) {
+        NoChooseVisitor("a nested item").visit_item_mut(item);
+    }
+}
+
+// Replaces every `choose!` with an error, for places where the machine
+//  can't yield from, like closures or nested coroutines.
+struct NoChooseVisitor(&'static str);
+
+impl NoChooseVisitor {
+    fn error(&self, mac: &Macro) -> Expr {
+        let error = syn::Error::new_spanned(
+            mac,
+            format_args!(
+                "choose! cannot be used inside {}, since the machine can't yield from there",
+                self.0
+            ),
+        )
+        .to_compile_error();
+
+        parse_quote! { { #error } }
+    }
+}
+
+impl VisitMut for NoChooseVisitor {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Macro(ExprMacro { mac, .. }) if is_choose(mac) => {
+                *expr = self.error(mac);
+            }
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::Macro(StmtMacro {
+                mac, semi_token, ..
+            }) if is_choose(mac) => {
+                *stmt = Stmt::Expr(self.error(mac), *semi_token);
+            }
+            _ => visit_mut::visit_stmt_mut(self, stmt),
+        }
     }
 }
 
@@ -42,61 +372,114 @@ impl Parse for MachineMacroInput {
 ///  machine, an initial value for the state, and a closure taking
 ///  no arguments, which will be the main loop of the state machine.
 ///
+/// Several state variables can be declared as `count: 0, step: 1`, in
+///  which case the state of the machine is a tuple of their values, or
+///  as the fields of a struct (`Form { count: 0, step: 1 }`), in which
+///  case each field is available as a variable, and the state of the
+///  machine is the struct built from their values.
+///
+/// The state variables may be followed by `state = <expr>`, to yield the
+///  value of `<expr>` as the state of the machine instead.
+///
 /// Inside the closure, there should be at least one `choose!`
 ///  statement, which prompts the executor of the state machine
 ///  to choose between a finte number of action to perform, and
 ///  returns the action that was selected.
 ///
+/// Besides plain actions, a `choose!` may offer an action only when
+///  some condition holds (`choose![Ok, Cancel if can_cancel]`), or
+///  every action produced by an iterator
+///  (`choose![Back, ..items.iter().map(Select)]`).
+///
 /// A `choose!` statement also yields the current value of the state
-///  variable back to the caller.
+///  variables back to the caller.
 ///
 #[proc_macro]
 pub fn machine(input: TokenStream) -> TokenStream {
-    let MachineMacroInput {
-        state_var,
-        initial_value,
-        body,
-    } = parse_macro_input!(input as MachineMacroInput);
-
-    struct ChooseVisitor<'a> {
-        state_var: &'a Ident,
-    }
-
-    impl<'a> syn::visit_mut::VisitMut for ChooseVisitor<'a> {
-        fn visit_stmt_mut(&mut self, stmt: &mut syn::Stmt) {
-            if let Stmt::Local(expr) = stmt {
-                if let Some(ref init) = expr.init {
-                    if let Expr::Macro(macro_exp) = &*init.expr {
-                        if macro_exp.mac.path.is_ident("choose") {
-                            let content = macro_exp.mac.tokens.clone();
-                            let state_var = self.state_var;
-                            let new_expr = parse_quote! {
-                                yield (#state_var, [#content].as_slice())
-                            };
-
-                            expr.init = Some(LocalInit {
-                                eq_token: init.eq_token,
-                                expr: new_expr,
-                                diverge: init.diverge.clone(),
-                            });
-                        }
-                    }
+    let MachineMacroInput { vars, state, body } = parse_macro_input!(input as MachineMacroInput);
+
+    let Some(vars) = vars else {
+        return syn::Error::new(
+            Span::call_site(),
+            "machine! requires at least one state variable",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    analysis::check_choices(&body);
+
+    let state = state.unwrap_or_else(|| vars.yielded());
+
+    let mut transformed_body = body;
+    let mut visitor = ChooseVisitor {
+        state: Some(&state),
+        asynchronous: false,
+    };
+    visitor.visit_expr_mut(&mut transformed_body);
+
+    let (vars, initial_values): (Vec<_>, Vec<_>) = vars.fields().into_iter().unzip();
+
+    quote! {
+        enstate::coroutines::AsMachine::new(
+            #[coroutine]
+            move |_| {
+                #(
+                    #[allow(unused_mut)]
+                    let mut #vars = #initial_values;
+                )*
+                loop {
+                    #transformed_body
                 }
             }
-        }
+        )
     }
+    .into()
+}
+
+///
+/// Macro to build a "standard" state machine whose main loop is async,
+///  and so may `.await` other futures between `choose!` statements.
+///
+/// This takes the same arguments as `machine!`, and requires the `alloc`
+///  feature of `enstate`. While the body is awaiting anything other than
+///  a choice, the state of the machine is `AsyncState::Pending`, and it
+///  has no edges until it is driven further with `AsAsyncMachine::poll`.
+///  If the body returns, the state is `AsyncState::Finished` from then on.
+///
+#[proc_macro]
+pub fn async_machine(input: TokenStream) -> TokenStream {
+    let MachineMacroInput { vars, state, body } = parse_macro_input!(input as MachineMacroInput);
+
+    let Some(vars) = vars else {
+        return syn::Error::new(
+            Span::call_site(),
+            "async_machine! requires at least one state variable",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    analysis::check_choices(&body);
+
+    let state = state.unwrap_or_else(|| vars.yielded());
 
     let mut transformed_body = body;
     let mut visitor = ChooseVisitor {
-        state_var: &state_var,
+        state: Some(&state),
+        asynchronous: true,
     };
-    syn::visit_mut::visit_expr_mut(&mut visitor, &mut transformed_body);
+    visitor.visit_expr_mut(&mut transformed_body);
+
+    let (vars, initial_values): (Vec<_>, Vec<_>) = vars.fields().into_iter().unzip();
 
     quote! {
-        enstate::coroutines::AsMachine::new(
-            #[coroutine]
-            |_| {
-                let mut #state_var = #initial_value;
+        enstate::coroutines::asynchronous::AsAsyncMachine::new(
+            move |__chooser| async move {
+                #(
+                    #[allow(unused_mut)]
+                    let mut #vars = #initial_values;
+                )*
                 loop {
                     #transformed_body
                 }
@@ -106,19 +489,117 @@ pub fn machine(input: TokenStream) -> TokenStream {
     .into()
 }
 
+///
+/// Macro to build a state machine which runs until it returns a result,
+///  for use with `Chainable`.
+///
+/// This macro takes a closure taking no arguments, which may use
+///  `choose!` in the same way as in `machine!`. The state of the
+///  machine is `None` until the closure returns, and then `Some` of the
+///  value it returned.
+///
+/// The closure may be preceded by state variables, declared in the same
+///  way as for `machine!`. In that case the state of the machine is a
+///  `Progress`, which is `InProgress` with the value of the state
+///  variables at each `choose!`, and `Done` with the returned value.
+///
 #[proc_macro]
 pub fn machine_chain(input: TokenStream) -> TokenStream {
-    let closure = parse_macro_input!(input as syn::ExprClosure);
+    let MachineMacroInput { vars, state, body } = parse_macro_input!(input as MachineMacroInput);
+
+    analysis::check_choices(&body);
+
+    let state = state.or_else(|| vars.as_ref().map(StateVars::yielded));
+
+    let mut transformed_body = body;
+    let mut visitor = ChooseVisitor {
+        state: state.as_ref(),
+        asynchronous: false,
+    };
+    visitor.visit_expr_mut(&mut transformed_body);
+
+    let (vars, initial_values): (Vec<_>, Vec<_>) = vars.iter().flat_map(StateVars::fields).unzip();
 
-    let body = closure.body;
+    let wrapper = match state {
+        Some(_) => quote! { enstate::coroutines::AsProgressMachine },
+        None => quote! { enstate::coroutines::AsChainMachine },
+    };
 
     quote! {
-        enstate::coroutines::AsChainMachine::new(
+        #wrapper::new(
             #[coroutine]
-            |_| {
-                #body
+            move |_| {
+                #(
+                    #[allow(unused_mut)]
+                    let mut #vars = #initial_values;
+                )*
+                #transformed_body
             }
         )
     }
     .into()
 }
+
+///
+/// Attribute to expose the actions offered by every `choose!` in a
+///  function as a constant, for documentation and tooling.
+///
+/// `#[edge_table(COUNTER_EDGES)]` on a function defines a constant
+///  `COUNTER_EDGES: &[&[&str]]` alongside it, with one entry per `choose!`
+///  (including those inside `machine!` and friends), in the order
+///  they appear in the source.
+///
+#[proc_macro_attribute]
+pub fn edge_table(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let name = parse_macro_input!(attr as Ident);
+    let function = parse_macro_input!(item as ItemFn);
+
+    let mut table = analysis::EdgeTable::default();
+    syn::visit::Visit::visit_item_fn(&mut table, &function);
+
+    let vis = &function.vis;
+    let doc = format!(
+        " Actions offered by each `choose!` in [`{}`].",
+        function.sig.ident
+    );
+    let choices = table
+        .choices
+        .iter()
+        .map(|choices| quote! { &[#(#choices),*] });
+
+    quote! {
+        #function
+
+        #[doc = #doc]
+        #vis const #name: &[&[&str]] = &[#(#choices),*];
+    }
+    .into()
+}
+
+///
+/// Derive a `Machine` for an enum of states, whose transitions are
+///  declared on each variant.
+///
+/// The type of transitions is given by `#[machine(transition = <type>)]`
+///  on the enum, and each variant lists the transitions out of it as
+///  `#[on(Edge => Target)]`, where `Edge` is a transition (`Coin` is
+///  short for `<transition type>::Coin`) and `Target` is the state to
+///  move to (`Paid` is short for `Self::Paid`). Leaving out `=> Target`
+///  stays in the same state.
+///
+/// A transition may also specify `guard = f`, where `f: fn(&Self) -> bool`
+///  decides whether the transition is currently offered, and
+///  `action = g`, where `g: fn(&mut Self, &Transition)` is called after
+///  moving to the target state.
+///
+/// The machine's state is a clone of the enum itself, and its transitions
+///  are compared with `PartialEq` when traversed.
+///
+#[proc_macro_derive(Machine, attributes(machine, on))]
+pub fn derive_machine(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    derive::derive_machine(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
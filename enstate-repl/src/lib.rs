@@ -0,0 +1,224 @@
+//!
+//! Headless interactive driver for exploring a machine from a terminal.
+//!
+//! The current state and numbered edges are printed after every step,
+//!  and a choice is read from the input, either as the index of an
+//!  edge or as text accepted by [Repl::parse_with]. Besides choices,
+//!  the following commands are understood:
+//!
+//!  - `undo`: step back to the previous state,
+//!  - `reset`: return to the initial state,
+//!  - `trace save <path>` / `trace load <path>`: store or replay the
+//!    choices made so far,
+//!  - `help` and `quit`.
+//!
+//! Sessions over chainable machines, like a `Machine<Option<T>>`, made
+//!  with [Repl::chained] end by themselves once the machine finishes.
+//!
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use enstate::machine::Machine;
+use enstate::machine::chained::Final;
+
+type Parser<E> = Box<dyn Fn(&str) -> Option<E>>;
+type Predicate<T> = Box<dyn Fn(&T) -> bool>;
+
+///
+/// Interactive session over a machine with state of type T.
+///
+/// The machine needs to be `Clone`, as `undo` and `reset` work by
+///  replaying the trace from a copy of the initial machine.
+///
+pub struct Repl<T, M: Machine<T>> {
+    t: PhantomData<T>,
+    initial: M,
+    machine: M,
+    trace: Vec<usize>,
+    parse: Option<Parser<M::Transition>>,
+    finished: Option<Predicate<T>>,
+}
+
+impl<T: Debug, M: Machine<T, Transition: Debug + PartialEq> + Clone> Repl<T, M> {
+    pub fn new(machine: M) -> Repl<T, M> {
+        Repl {
+            t: PhantomData,
+            initial: machine.clone(),
+            machine,
+            trace: Vec::new(),
+            parse: None,
+            finished: None,
+        }
+    }
+
+    ///
+    /// Create a session over a chainable machine, which ends once the
+    ///  machine is finished.
+    ///
+    pub fn chained(machine: M) -> Repl<T, M>
+    where
+        T: Final + 'static,
+    {
+        Repl::new(machine).until(T::is_final)
+    }
+
+    ///
+    /// Accept choices written as text, for example with `str::parse`
+    ///  for transitions implementing `FromStr`.
+    ///
+    pub fn parse_with(self, parse: impl Fn(&str) -> Option<M::Transition> + 'static) -> Self {
+        Repl {
+            parse: Some(Box::new(parse)),
+            ..self
+        }
+    }
+
+    ///
+    /// End the session once the state satisfies a predicate.
+    ///
+    pub fn until(self, finished: impl Fn(&T) -> bool + 'static) -> Self {
+        Repl {
+            finished: Some(Box::new(finished)),
+            ..self
+        }
+    }
+
+    ///
+    /// Indices into `edges()` of the choices made since the initial state.
+    ///
+    pub fn trace(&self) -> &[usize] {
+        &self.trace
+    }
+
+    pub fn machine(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    ///
+    /// Run the session until the machine finishes, `quit` is entered or
+    ///  the input ends, returning the final state if the machine finished.
+    ///
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<Option<T>> {
+        let mut lines = input.lines();
+
+        loop {
+            let state = self.machine.state();
+            if let Some(finished) = &self.finished
+                && finished(&state)
+            {
+                writeln!(output, "finished: {state:?}")?;
+                return Ok(Some(state));
+            }
+
+            writeln!(output, "state: {state:?}")?;
+            for (index, edge) in self.machine.edges().enumerate() {
+                writeln!(output, "  {index}: {edge:?}")?;
+            }
+            write!(output, "> ")?;
+            output.flush()?;
+
+            let Some(line) = lines.next() else {
+                return Ok(None);
+            };
+            let line = line?;
+            let mut words = line.split_whitespace();
+
+            match (words.next(), words.next(), words.next()) {
+                (None, ..) => {}
+                (Some("quit"), None, _) => return Ok(None),
+                (Some("help"), None, _) => writeln!(
+                    output,
+                    "commands: <index>, undo, reset, trace save <path>, trace load <path>, quit"
+                )?,
+                (Some("undo"), None, _) => {
+                    if self.trace.pop().is_none() {
+                        writeln!(output, "nothing to undo")?;
+                    }
+                    self.replay();
+                }
+                (Some("reset"), None, _) => {
+                    self.trace.clear();
+                    self.replay();
+                }
+                (Some("trace"), Some("save"), Some(path)) => {
+                    if let Err(error) = fs::write(path, self.trace_text()) {
+                        writeln!(output, "could not save trace: {error}")?;
+                    }
+                }
+                (Some("trace"), Some("load"), Some(path)) => {
+                    if let Err(error) = self.load(path) {
+                        writeln!(output, "could not load trace: {error}")?;
+                    }
+                }
+                _ => match self.pick(line.trim()) {
+                    Some(index) => self.step(index),
+                    None => writeln!(output, "unknown choice: {}", line.trim())?,
+                },
+            }
+        }
+    }
+
+    ///
+    /// Run the session on standard input and output.
+    ///
+    pub fn run_stdio(&mut self) -> io::Result<Option<T>> {
+        self.run(io::stdin().lock(), io::stdout().lock())
+    }
+
+    fn pick(&self, choice: &str) -> Option<usize> {
+        let count = self.machine.edges().count();
+        if let Ok(index) = choice.parse::<usize>() {
+            return (index < count).then_some(index);
+        }
+
+        let edge = self.parse.as_ref()?(choice)?;
+        self.machine.edges().position(|offered| offered == edge)
+    }
+
+    fn step(&mut self, index: usize) {
+        let edge = self.machine.edges().nth(index);
+        if let Some(edge) = edge {
+            self.machine.traverse(&edge);
+            self.trace.push(index);
+        }
+    }
+
+    fn replay(&mut self) {
+        self.machine = self.initial.clone();
+        for index in core::mem::take(&mut self.trace) {
+            self.step(index);
+        }
+    }
+
+    // One choice per line, as its index followed by the edge for reference.
+    fn trace_text(&self) -> String {
+        let mut machine = self.initial.clone();
+        let mut text = String::new();
+        for &index in &self.trace {
+            let edge = machine.edges().nth(index);
+            let edge = edge.expect("trace was recorded");
+            text.push_str(&format!("{index} {edge:?}\n"));
+            machine.traverse(&edge);
+        }
+        text
+    }
+
+    fn load(&mut self, path: &str) -> io::Result<()> {
+        let text = fs::read_to_string(path)?;
+        let mut trace = Vec::new();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let index = line
+                .split_whitespace()
+                .next()
+                .and_then(|index| index.parse().ok())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, line.to_string()))?;
+            trace.push(index);
+        }
+
+        self.trace = trace;
+        self.replay();
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+#![feature(coroutines, coroutine_clone, exhaustive_patterns)]
+
+//!
+//! Demo session over a small checkout, ending once it is paid.
+//!
+use std::str::FromStr;
+
+use enstate::machine::Machine;
+use enstate_macros::machine_chain;
+use enstate_repl::Repl;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Action {
+    Add,
+    Remove,
+    Pay,
+}
+
+impl FromStr for Action {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "add" => Ok(Action::Add),
+            "remove" => Ok(Action::Remove),
+            "pay" => Ok(Action::Pay),
+            _ => Err(()),
+        }
+    }
+}
+
+fn checkout() -> impl Machine<Option<u32>, Transition = Action> + Clone {
+    machine_chain!(|| {
+        let mut items = 0;
+        loop {
+            match choose![Action::Add, Action::Remove if items > 0, Action::Pay if items > 0] {
+                Action::Add => items += 1,
+                Action::Remove => items -= 1,
+                Action::Pay => return items,
+            }
+        }
+    })
+}
+
+fn main() -> std::io::Result<()> {
+    Repl::chained(checkout())
+        .parse_with(|s| s.parse().ok())
+        .run_stdio()?;
+    Ok(())
+}
@@ -0,0 +1,92 @@
+#![feature(coroutines, coroutine_clone, exhaustive_patterns)]
+
+use std::str::FromStr;
+
+use enstate::machine::Machine;
+use enstate_macros::machine_chain;
+use enstate_repl::Repl;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Add,
+    Remove,
+    Checkout,
+}
+
+impl FromStr for Action {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "add" => Ok(Action::Add),
+            "remove" => Ok(Action::Remove),
+            "checkout" => Ok(Action::Checkout),
+            _ => Err(()),
+        }
+    }
+}
+
+///
+/// A basket which is finished once it is checked out with its item count.
+///
+fn basket() -> impl Machine<Option<u32>, Transition = Action> + Clone {
+    machine_chain!(|| {
+        let mut items = 0;
+        loop {
+            match choose![Action::Add, Action::Remove if items > 0, Action::Checkout] {
+                Action::Add => items += 1,
+                Action::Remove => items -= 1,
+                Action::Checkout => return items,
+            }
+        }
+    })
+}
+
+#[test]
+fn repl_example() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("basket.trace");
+    let path = path.display();
+
+    let input = format!(
+        "add\n0\n1\nundo\ntrace save {path}\nreset\nbogus\ntrace load {path}\ncheckout\n"
+    );
+    let mut output = Vec::new();
+
+    let mut repl = Repl::new(basket())
+        .parse_with(|s| s.parse().ok())
+        .until(Option::is_some);
+    let result = repl.run(input.as_bytes(), &mut output).unwrap();
+
+    assert_eq!(result, Some(Some(2)));
+    assert_eq!(repl.trace(), &[0, 0, 2]);
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("state: None\n  0: Add\n  1: Checkout\n> "));
+    assert!(output.contains("  1: Remove\n"));
+    assert!(output.contains("unknown choice: bogus\n"));
+    assert!(output.ends_with("finished: Some(2)\n"));
+
+    assert_eq!(
+        std::fs::read_to_string(path.to_string()).unwrap(),
+        "0 Add\n0 Add\n"
+    );
+}
+
+#[test]
+fn repl_chained_example() {
+    let input = "add\ncheckout\nadd\n";
+    let mut output = Vec::new();
+
+    // The session ends as soon as the basket is checked out.
+    let mut repl = Repl::chained(basket()).parse_with(|s| s.parse().ok());
+    let result = repl.run(input.as_bytes(), &mut output).unwrap();
+
+    assert_eq!(result, Some(Some(1)));
+    assert_eq!(repl.trace(), &[0, 2]);
+    assert!(
+        String::from_utf8(output)
+            .unwrap()
+            .ends_with("finished: Some(1)\n")
+    );
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+use std::{env, fs};
+
+///
+/// Environment variable which, when set, makes [assert_golden] write the
+///  actual output to the golden file instead of comparing against it.
+///
+pub const BLESS_VAR: &str = "ENSTATE_BLESS";
+
+///
+/// Compare some output, such as a recorded trace, against the contents
+///  of a checked-in golden file.
+///
+/// Run the tests with `ENSTATE_BLESS=1` to create or update the file
+///  after an intended change of behaviour.
+///
+#[track_caller]
+pub fn assert_golden(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+
+    if env::var_os(BLESS_VAR).is_some_and(|bless| !bless.is_empty()) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("could not create golden file directory");
+        }
+        fs::write(path, actual).expect("could not write golden file");
+        return;
+    }
+
+    let Ok(expected) = fs::read_to_string(path) else {
+        panic!(
+            "golden file {} is missing, run with {BLESS_VAR}=1 to create it",
+            path.display()
+        );
+    };
+
+    if expected != actual {
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+
+        panic!(
+            "output differs from golden file {} at line {}, run with {BLESS_VAR}=1 to update it\n\
+             --- expected\n{expected}\n--- actual\n{actual}",
+            path.display(),
+            line + 1,
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//!
+//! Helpers for testing machines declaratively.
+//!
+
+mod golden;
+mod scenario;
+
+pub use golden::{BLESS_VAR, assert_golden};
+pub use scenario::{Scenario, given};
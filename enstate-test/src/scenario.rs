@@ -0,0 +1,118 @@
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use enstate::machine::Machine;
+
+///
+/// Start a scenario driving a machine, checking its behaviour step by step.
+///
+/// ```ignore
+/// given(dialog())
+///     .then_state(None)
+///     .on(Action::Increment)
+///     .then_edges_contain(ModalAction::Ok)
+///     .on(ModalAction::Ok)
+///     .then_state(Some(ModalResult::Ok(1)));
+/// ```
+///
+/// When a step fails, the panic message lists every step up to the one
+///  that failed, along with the edges the machine allowed at that point.
+///
+pub fn given<T, M: Machine<T>>(machine: M) -> Scenario<T, M> {
+    Scenario {
+        t: PhantomData,
+        machine,
+        steps: Vec::new(),
+    }
+}
+
+pub struct Scenario<T, M> {
+    t: PhantomData<T>,
+    machine: M,
+    steps: Vec<String>,
+}
+
+impl<T, M> Scenario<T, M>
+where
+    T: PartialEq + Debug,
+    M: Machine<T, Transition: PartialEq + Debug>,
+{
+    ///
+    /// Traverse an edge, which has to be allowed by the machine.
+    ///
+    #[track_caller]
+    pub fn on(mut self, edge: impl Into<M::Transition>) -> Self {
+        let edge = edge.into();
+        self.steps.push(format!("on({edge:?})"));
+
+        if !self.machine.edges().any(|offered| offered == edge) {
+            self.fail(format_args!("edge {edge:?} is not allowed"));
+        }
+
+        self.machine.traverse(&edge);
+        self
+    }
+
+    #[track_caller]
+    pub fn then_state(mut self, expected: T) -> Self {
+        self.steps.push(format!("then_state({expected:?})"));
+
+        let state = self.machine.state();
+        if state != expected {
+            self.fail(format_args!("expected state {expected:?}, found {state:?}"));
+        }
+
+        self
+    }
+
+    #[track_caller]
+    pub fn then_edges_contain(mut self, edge: impl Into<M::Transition>) -> Self {
+        let edge = edge.into();
+        self.steps.push(format!("then_edges_contain({edge:?})"));
+
+        if !self.machine.edges().any(|offered| offered == edge) {
+            self.fail(format_args!("expected edge {edge:?} to be allowed"));
+        }
+
+        self
+    }
+
+    ///
+    /// Check the allowed edges exactly, in the order they are offered.
+    ///
+    #[track_caller]
+    pub fn then_edges(mut self, expected: impl IntoIterator<Item: Into<M::Transition>>) -> Self {
+        let expected: Vec<M::Transition> = expected.into_iter().map(Into::into).collect();
+        self.steps.push(format!("then_edges({expected:?})"));
+
+        let edges: Vec<M::Transition> = self.machine.edges().collect();
+        if edges != expected {
+            self.fail(format_args!("expected edges {expected:?}, found {edges:?}"));
+        }
+
+        self
+    }
+
+    pub fn into_machine(self) -> M {
+        self.machine
+    }
+
+    #[track_caller]
+    fn fail(&self, reason: core::fmt::Arguments<'_>) -> ! {
+        let mut message = format!("scenario failed at step {}: {reason}\n", self.steps.len());
+        for (index, step) in self.steps.iter().enumerate() {
+            let marker = if index + 1 == self.steps.len() {
+                '>'
+            } else {
+                ' '
+            };
+            message.push_str(&format!("{marker} {}. {step}\n", index + 1));
+        }
+        message.push_str(&format!(
+            "allowed edges: {:?}",
+            self.machine.edges().collect::<Vec<_>>()
+        ));
+
+        panic!("{message}");
+    }
+}
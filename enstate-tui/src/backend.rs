@@ -0,0 +1,275 @@
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+///
+/// Key presses understood by the driver.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Key {
+    Up,
+    Down,
+    Enter,
+    Esc,
+    Char(char),
+}
+
+///
+/// Terminal the driver draws frames to and reads keys from.
+///
+pub trait Backend {
+    ///
+    /// Replace the contents of the screen with the given lines.
+    ///
+    fn draw(&mut self, lines: &[String]) -> io::Result<()>;
+
+    ///
+    /// Wait for the next key press, or `None` once input has ended.
+    ///
+    fn read_key(&mut self) -> io::Result<Option<Key>>;
+}
+
+///
+/// In-memory backend which replays a fixed sequence of keys and keeps
+///  every frame that was drawn, for use in tests.
+///
+pub struct TestBackend {
+    keys: VecDeque<Key>,
+    frames: Vec<Vec<String>>,
+}
+
+impl TestBackend {
+    pub fn new(keys: impl IntoIterator<Item = Key>) -> TestBackend {
+        TestBackend {
+            keys: keys.into_iter().collect(),
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn frames(&self) -> &[Vec<String>] {
+        &self.frames
+    }
+
+    ///
+    /// The last frame that was drawn, as a single string.
+    ///
+    pub fn screen(&self) -> String {
+        self.frames
+            .last()
+            .map(|lines| lines.join("\n"))
+            .unwrap_or_default()
+    }
+}
+
+impl Backend for TestBackend {
+    fn draw(&mut self, lines: &[String]) -> io::Result<()> {
+        self.frames.push(lines.to_vec());
+        Ok(())
+    }
+
+    fn read_key(&mut self) -> io::Result<Option<Key>> {
+        Ok(self.keys.pop_front())
+    }
+}
+
+///
+/// How long to wait for the rest of an escape sequence before treating
+///  the escape byte as the Esc key on its own.
+///
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(50);
+
+///
+/// Backend for terminals understanding ANSI escape sequences, such as
+///  standard input and output.
+///
+/// Raw mode is not required: without it keys are only seen once a line
+///  has been entered, and the newline entering a number is ignored so
+///  that it does not select a second time.
+///
+/// Input is read on a separate thread, one byte at a time and only when
+///  a key is being read, so that a bare Esc can be told apart from the
+///  start of an escape sequence without blocking. The thread is stopped
+///  when the backend is dropped, after finishing a read still waiting
+///  for the byte following a bare Esc, if any.
+///
+pub struct AnsiBackend<W> {
+    requests: Option<Sender<()>>,
+    input: Receiver<io::Result<Option<u8>>>,
+    reader: Option<JoinHandle<()>>,
+    // Whether a byte was asked for which has not been received yet.
+    waiting: bool,
+    // Bytes read while looking for an escape sequence, but not part of it.
+    pending: VecDeque<u8>,
+    after_digit: bool,
+    output: W,
+}
+
+impl<W: Write> AnsiBackend<W> {
+    pub fn new(mut input: impl Read + Send + 'static, output: W) -> AnsiBackend<W> {
+        let (requests, requested) = mpsc::channel();
+        let (sender, receiver) = mpsc::channel();
+
+        let reader = thread::spawn(move || {
+            while requested.recv().is_ok() {
+                let mut byte = [0];
+                let read = loop {
+                    match input.read(&mut byte) {
+                        Ok(0) => break Ok(None),
+                        Ok(_) => break Ok(Some(byte[0])),
+                        Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                        Err(error) => break Err(error),
+                    }
+                };
+
+                if sender.send(read).is_err() {
+                    break;
+                }
+            }
+        });
+
+        AnsiBackend {
+            requests: Some(requests),
+            input: receiver,
+            reader: Some(reader),
+            waiting: false,
+            pending: VecDeque::new(),
+            after_digit: false,
+            output,
+        }
+    }
+
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        self.receive(None)
+    }
+
+    // The next byte if it arrives soon enough to be part of an escape sequence.
+    fn read_byte_soon(&mut self) -> io::Result<Option<u8>> {
+        self.receive(Some(ESCAPE_TIMEOUT))
+    }
+
+    fn receive(&mut self, timeout: Option<Duration>) -> io::Result<Option<u8>> {
+        if let Some(byte) = self.pending.pop_front() {
+            return Ok(Some(byte));
+        }
+
+        if !self.waiting {
+            let requests = self.requests.as_ref().expect("backend is not dropped");
+            if requests.send(()).is_err() {
+                return Ok(None);
+            }
+            self.waiting = true;
+        }
+
+        let received = match timeout {
+            None => self.input.recv().ok(),
+            Some(timeout) => match self.input.recv_timeout(timeout) {
+                Ok(read) => Some(read),
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                Err(RecvTimeoutError::Disconnected) => None,
+            },
+        };
+
+        self.waiting = false;
+        received.unwrap_or(Ok(None))
+    }
+
+    // Read the rest of an escape sequence, returning `None` for control
+    //  sequences which are not understood.
+    fn read_escape(&mut self) -> io::Result<Option<Key>> {
+        match self.read_byte_soon()? {
+            Some(b'[') => {}
+            Some(byte) => {
+                self.pending.push_front(byte);
+                return Ok(Some(Key::Esc));
+            }
+            None => return Ok(Some(Key::Esc)),
+        }
+
+        // Parameter and intermediate bytes, up to the final byte.
+        loop {
+            match self.read_byte_soon()? {
+                Some(b'A') => return Ok(Some(Key::Up)),
+                Some(b'B') => return Ok(Some(Key::Down)),
+                Some(0x40..=0x7e) | None => return Ok(None),
+                Some(0x20..=0x3f) => {}
+                Some(byte) => {
+                    self.pending.push_front(byte);
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn read_char(&mut self, first: u8) -> io::Result<Key> {
+        let len = match first {
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => 1,
+        };
+
+        let mut bytes = vec![first];
+        while bytes.len() < len {
+            match self.read_byte()? {
+                Some(byte) if byte & 0xc0 == 0x80 => bytes.push(byte),
+                Some(byte) => {
+                    self.pending.push_front(byte);
+                    break;
+                }
+                None => break,
+            }
+        }
+
+        let c = str::from_utf8(&bytes)
+            .ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+
+        Ok(Key::Char(c))
+    }
+}
+
+impl<W: Write> Backend for AnsiBackend<W> {
+    fn draw(&mut self, lines: &[String]) -> io::Result<()> {
+        write!(self.output, "\x1b[2J\x1b[H")?;
+        for line in lines {
+            write!(self.output, "{line}\r\n")?;
+        }
+        self.output.flush()
+    }
+
+    fn read_key(&mut self) -> io::Result<Option<Key>> {
+        loop {
+            let key = match self.read_byte()? {
+                None => return Ok(None),
+                Some(b'\n') if self.after_digit => {
+                    self.after_digit = false;
+                    continue;
+                }
+                Some(b'\r' | b'\n') => Key::Enter,
+                Some(0x1b) => match self.read_escape()? {
+                    Some(key) => key,
+                    None => continue,
+                },
+                Some(byte) => self.read_char(byte)?,
+            };
+
+            self.after_digit = matches!(key, Key::Char('0'..='9'));
+            return Ok(Some(key));
+        }
+    }
+}
+
+impl<W> Drop for AnsiBackend<W> {
+    fn drop(&mut self) {
+        // Without requests the reader stops, unless it is blocked reading.
+        self.requests.take();
+        if !self.waiting
+            && let Some(reader) = self.reader.take()
+        {
+            let _ = reader.join();
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//!
+//! Terminal driver presenting the edges of a machine as a menu.
+//!
+//! The state is rendered through user functions, and the edges that are
+//!  currently allowed are listed below it and selected with the arrow keys
+//!  (or `j`/`k`) and enter, or directly by their number. Composed machines
+//!  can be split into sections, each rendering part of the state along
+//!  with the edges belonging to that component.
+//!
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use std::io;
+
+use enstate::machine::Machine;
+
+mod backend;
+
+pub use backend::{AnsiBackend, Backend, Key, TestBackend};
+
+type Render<T> = Box<dyn Fn(&T) -> String>;
+type Predicate<T> = Box<dyn Fn(&T) -> bool>;
+
+struct Section<T, E> {
+    title: String,
+    render: Render<T>,
+    offers: Predicate<E>,
+}
+
+///
+/// Menu driven session over a machine with state of type T.
+///
+pub struct Tui<T, M: Machine<T>> {
+    t: PhantomData<T>,
+    machine: M,
+    sections: Vec<Section<T, M::Transition>>,
+    finished: Option<Predicate<T>>,
+    selected: usize,
+}
+
+impl<T: Debug, M: Machine<T, Transition: Debug>> Tui<T, M> {
+    ///
+    /// Create a driver which renders the state with `Debug` until
+    ///  sections are added.
+    ///
+    pub fn new(machine: M) -> Tui<T, M> {
+        Tui {
+            t: PhantomData,
+            machine,
+            sections: Vec::new(),
+            finished: None,
+            selected: 0,
+        }
+    }
+
+    ///
+    /// Add a section rendering the state with `render`, and listing the
+    ///  edges for which `offers` holds.
+    ///
+    /// Edges are listed in the first section offering them, and edges
+    ///  offered by no section are listed at the end.
+    ///
+    pub fn section(
+        mut self,
+        title: impl Into<String>,
+        render: impl Fn(&T) -> String + 'static,
+        offers: impl Fn(&M::Transition) -> bool + 'static,
+    ) -> Self {
+        self.sections.push(Section {
+            title: title.into(),
+            render: Box::new(render),
+            offers: Box::new(offers),
+        });
+        self
+    }
+
+    ///
+    /// End the session once the state satisfies a predicate.
+    ///
+    pub fn until(self, finished: impl Fn(&T) -> bool + 'static) -> Self {
+        Tui {
+            finished: Some(Box::new(finished)),
+            ..self
+        }
+    }
+
+    pub fn machine(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    ///
+    /// Run the session until the machine finishes, `q` or escape is
+    ///  pressed or the input ends, returning the final state if the
+    ///  machine finished.
+    ///
+    pub fn run(&mut self, backend: &mut impl Backend) -> io::Result<Option<T>> {
+        loop {
+            let state = self.machine.state();
+            if let Some(finished) = &self.finished
+                && finished(&state)
+            {
+                backend.draw(&self.frame(&state, &[]))?;
+                return Ok(Some(state));
+            }
+
+            let edges = self.menu();
+            self.selected = self.selected.min(edges.len().saturating_sub(1));
+            backend.draw(&self.frame(&state, &edges))?;
+
+            let chosen = match backend.read_key()? {
+                None | Some(Key::Esc | Key::Char('q')) => return Ok(None),
+                Some(Key::Up | Key::Char('k')) => {
+                    self.selected = self.selected.saturating_sub(1);
+                    None
+                }
+                Some(Key::Down | Key::Char('j')) => {
+                    self.selected += 1;
+                    None
+                }
+                Some(Key::Enter) => Some(self.selected),
+                Some(Key::Char(digit @ '1'..='9')) => Some(digit as usize - '1' as usize),
+                Some(Key::Char(_)) => None,
+            };
+
+            if let Some((_, edge)) = chosen.and_then(|index| edges.into_iter().nth(index)) {
+                self.machine.traverse(&edge);
+                self.selected = 0;
+            }
+        }
+    }
+
+    // Current edges tagged with the section listing them, in menu order.
+    fn menu(&self) -> Vec<(Option<usize>, M::Transition)> {
+        let mut edges: Vec<_> = self
+            .machine
+            .edges()
+            .map(|edge| {
+                let section = self
+                    .sections
+                    .iter()
+                    .position(|section| (section.offers)(&edge));
+                (section, edge)
+            })
+            .collect();
+
+        edges.sort_by_key(|(section, _)| section.unwrap_or(self.sections.len()));
+        edges
+    }
+
+    fn frame(&self, state: &T, edges: &[(Option<usize>, M::Transition)]) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        if self.sections.is_empty() {
+            lines.extend(format!("{state:?}").lines().map(String::from));
+        }
+
+        for (index, section) in self.sections.iter().enumerate() {
+            if index > 0 {
+                lines.push(String::new());
+            }
+            lines.push(format!("[ {} ]", section.title));
+            lines.extend((section.render)(state).lines().map(String::from));
+            self.list(&mut lines, edges, Some(index));
+        }
+
+        if !self.sections.is_empty() && edges.iter().any(|(section, _)| section.is_none()) {
+            lines.push(String::new());
+        }
+        self.list(&mut lines, edges, None);
+
+        lines
+    }
+
+    fn list(
+        &self,
+        lines: &mut Vec<String>,
+        edges: &[(Option<usize>, M::Transition)],
+        section: Option<usize>,
+    ) {
+        for (index, (_, edge)) in edges.iter().enumerate().filter(|(_, (s, _))| *s == section) {
+            let marker = if index == self.selected { '>' } else { ' ' };
+            lines.push(format!("{marker} {}. {edge:?}", index + 1));
+        }
+    }
+}
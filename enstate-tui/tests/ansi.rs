@@ -0,0 +1,72 @@
+use std::io::{self, Read, Write};
+use std::thread;
+use std::time::Duration;
+
+use enstate_tui::{AnsiBackend, Backend, Key};
+
+fn keys(input: &'static [u8]) -> Vec<Key> {
+    let mut backend = AnsiBackend::new(input, io::sink());
+    let mut keys = Vec::new();
+    while let Some(key) = backend.read_key().unwrap() {
+        keys.push(key);
+    }
+    keys
+}
+
+#[test]
+fn ansi_keys_example() {
+    assert_eq!(
+        keys(b"\x1b[A\x1b[Bj\r"),
+        [Key::Up, Key::Down, Key::Char('j'), Key::Enter]
+    );
+
+    // Keys following a bare Esc are not swallowed.
+    assert_eq!(keys(b"\x1bq"), [Key::Esc, Key::Char('q')]);
+
+    // Control sequences which are not understood are skipped as a whole.
+    assert_eq!(keys(b"\x1b[x"), []);
+    assert_eq!(keys(b"\x1b[C\x1b[3~\x1b[1;5Dq"), [Key::Char('q')]);
+
+    // Without raw mode, the newline entering a number is not another key.
+    assert_eq!(keys(b"2\n\n"), [Key::Char('2'), Key::Enter]);
+
+    assert_eq!(
+        keys("é→😀".as_bytes()),
+        [Key::Char('é'), Key::Char('→'), Key::Char('😀')]
+    );
+    assert_eq!(keys(b"\xff"), [Key::Char(char::REPLACEMENT_CHARACTER)]);
+}
+
+#[test]
+fn ansi_bare_esc_example() {
+    let (reader, mut writer) = io::pipe().unwrap();
+    let mut backend = AnsiBackend::new(reader, io::sink());
+
+    // A bare Esc is reported without waiting for more input.
+    writer.write_all(b"\x1b").unwrap();
+    assert_eq!(backend.read_key().unwrap(), Some(Key::Esc));
+
+    let typing = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        writer.write_all(b"k").unwrap();
+    });
+    assert_eq!(backend.read_key().unwrap(), Some(Key::Char('k')));
+    typing.join().unwrap();
+}
+
+#[test]
+fn ansi_drop_example() {
+    let (reader, mut writer) = io::pipe().unwrap();
+    let mut rest = reader.try_clone().unwrap();
+    let mut backend = AnsiBackend::new(reader, io::sink());
+
+    writer.write_all(b"a").unwrap();
+    assert_eq!(backend.read_key().unwrap(), Some(Key::Char('a')));
+
+    // Input following the session is left for whatever reads it next.
+    drop(backend);
+    writer.write_all(b"b").unwrap();
+    let mut byte = [0];
+    rest.read_exact(&mut byte).unwrap();
+    assert_eq!(&byte, b"b");
+}
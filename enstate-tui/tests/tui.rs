@@ -0,0 +1,127 @@
+#![feature(coroutines, exhaustive_patterns)]
+
+use std::marker::PhantomData;
+
+use enstate::machine::Machine;
+use enstate_macros::{machine, machine_chain};
+use enstate_tui::{Key, TestBackend, Tui};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Increment,
+    Decrement,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ModalAction {
+    Ok,
+    Cancel,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DialogAction {
+    Buttons(ModalAction),
+    Display(Action),
+}
+
+impl From<ModalAction> for DialogAction {
+    fn from(value: ModalAction) -> Self {
+        DialogAction::Buttons(value)
+    }
+}
+
+impl From<Action> for DialogAction {
+    fn from(value: Action) -> Self {
+        DialogAction::Display(value)
+    }
+}
+
+impl TryInto<Action> for DialogAction {
+    type Error = ();
+
+    fn try_into(self) -> Result<Action, Self::Error> {
+        match self {
+            DialogAction::Display(action) => Ok(action),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<ModalAction> for DialogAction {
+    type Error = ();
+
+    fn try_into(self) -> Result<ModalAction, Self::Error> {
+        match self {
+            DialogAction::Buttons(action) => Ok(action),
+            _ => Err(()),
+        }
+    }
+}
+
+fn counter() -> impl Machine<i32, Transition = Action> {
+    machine!(count, 0, || {
+        match choose![Action::Increment, Action::Decrement] {
+            Action::Increment => count += 1,
+            Action::Decrement => count -= 1,
+        }
+    })
+}
+
+fn modal() -> impl Machine<Option<bool>, Transition = ModalAction> {
+    machine_chain!(|| {
+        match choose![ModalAction::Ok, ModalAction::Cancel] {
+            ModalAction::Ok => true,
+            ModalAction::Cancel => false,
+        }
+    })
+}
+
+#[test]
+fn tui_example() {
+    let machine = modal().zip_with_into(PhantomData, counter(), |ok, count| {
+        (ok.map(|ok| ok.then_some(count)), count)
+    });
+
+    let mut tui = Tui::new(machine)
+        .section(
+            "Dialog",
+            |(result, _)| format!("{result:?}"),
+            |edge| matches!(edge, DialogAction::Buttons(_)),
+        )
+        .section(
+            "Counter",
+            |(_, count)| format!("count = {count}"),
+            |edge| matches!(edge, DialogAction::Display(_)),
+        )
+        .until(|(result, _)| result.is_some());
+
+    let mut backend = TestBackend::new([
+        Key::Down,
+        Key::Down,
+        Key::Enter,
+        Key::Char('3'),
+        Key::Char('1'),
+    ]);
+    let result = tui.run(&mut backend).unwrap();
+
+    assert_eq!(result, Some((Some(Some(2)), 2)));
+    assert_eq!(
+        backend.frames()[2].join("\n"),
+        [
+            "[ Dialog ]",
+            "None",
+            "  1. Buttons(Ok)",
+            "  2. Buttons(Cancel)",
+            "",
+            "[ Counter ]",
+            "count = 0",
+            "> 3. Display(Increment)",
+            "  4. Display(Decrement)",
+        ]
+        .join("\n")
+    );
+    assert_eq!(
+        backend.screen(),
+        "[ Dialog ]\nSome(Some(2))\n\n[ Counter ]\ncount = 2"
+    );
+}
@@ -0,0 +1,12 @@
+//!
+//! Tools for inspecting the behaviour of machines by exhaustively
+//!  exploring their `edges()`, rather than by driving them by hand.
+//!
+//! These are mostly intended to be used from tests, and so require
+//!  the `alloc` feature.
+//!
+
+pub mod equivalence;
+pub mod markov;
+pub mod table;
+pub mod trace;
@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+
+use crate::machine::Machine;
+
+///
+/// Describes how two machines disagreed at the end of a
+///  [Counterexample] trace.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Divergence<E, T, U> {
+    /// The machines offered different sets of edges.
+    Edges { left: Vec<E>, right: Vec<E> },
+    /// The machines offered the same edges, but their states were
+    ///  not equivalent.
+    State { left: T, right: U },
+}
+
+///
+/// A trace of transitions after which two machines stopped behaving
+///  the same way.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counterexample<E, T, U> {
+    pub trace: Vec<E>,
+    pub divergence: Divergence<E, T, U>,
+}
+
+///
+/// Explore two machines in lockstep, checking that after every trace of
+///  at most `depth` transitions they expose the same `edges()` and
+///  equivalent states according to `eq`.
+///
+/// Edges are compared as sets, so two machines offering the same
+///  transitions in a different order are still considered equivalent.
+///
+/// Returns the first trace found along which the machines diverge.
+///
+pub fn check_equivalence_by<T, U, M1, M2, F>(
+    machine1: &M1,
+    machine2: &M2,
+    depth: usize,
+    mut eq: F,
+) -> Result<(), Counterexample<M1::Transition, T, U>>
+where
+    M1: Machine<T> + Clone,
+    M2: Machine<U, Transition = M1::Transition> + Clone,
+    M1::Transition: Clone + PartialEq,
+    F: FnMut(&T, &U) -> bool,
+{
+    let mut trace = Vec::new();
+    explore(machine1, machine2, depth, &mut eq, &mut trace)
+}
+
+///
+/// Same as [check_equivalence_by], comparing states with `PartialEq`.
+///
+pub fn check_equivalence<T, U, M1, M2>(
+    machine1: &M1,
+    machine2: &M2,
+    depth: usize,
+) -> Result<(), Counterexample<M1::Transition, T, U>>
+where
+    M1: Machine<T> + Clone,
+    M2: Machine<U, Transition = M1::Transition> + Clone,
+    M1::Transition: Clone + PartialEq,
+    T: PartialEq<U>,
+{
+    check_equivalence_by(machine1, machine2, depth, |x, y| x == y)
+}
+
+fn explore<T, U, M1, M2, F>(
+    machine1: &M1,
+    machine2: &M2,
+    depth: usize,
+    eq: &mut F,
+    trace: &mut Vec<M1::Transition>,
+) -> Result<(), Counterexample<M1::Transition, T, U>>
+where
+    M1: Machine<T> + Clone,
+    M2: Machine<U, Transition = M1::Transition> + Clone,
+    M1::Transition: Clone + PartialEq,
+    F: FnMut(&T, &U) -> bool,
+{
+    let left: Vec<_> = machine1.edges().collect();
+    let right: Vec<_> = machine2.edges().collect();
+
+    let same_edges =
+        left.iter().all(|e| right.contains(e)) && right.iter().all(|e| left.contains(e));
+
+    if !same_edges {
+        return Err(Counterexample {
+            trace: trace.clone(),
+            divergence: Divergence::Edges { left, right },
+        });
+    }
+
+    let state1 = machine1.clone().state();
+    let state2 = machine2.clone().state();
+
+    if !eq(&state1, &state2) {
+        return Err(Counterexample {
+            trace: trace.clone(),
+            divergence: Divergence::State {
+                left: state1,
+                right: state2,
+            },
+        });
+    }
+
+    if depth == 0 {
+        return Ok(());
+    }
+
+    for edge in left {
+        let mut next1 = machine1.clone();
+        let mut next2 = machine2.clone();
+
+        next1.traverse(&edge);
+        next2.traverse(&edge);
+
+        trace.push(edge);
+        explore(&next1, &next2, depth - 1, eq, trace)?;
+        trace.pop();
+    }
+
+    Ok(())
+}
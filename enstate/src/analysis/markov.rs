@@ -0,0 +1,266 @@
+use alloc::{vec, vec::Vec};
+
+use super::table::compile_by_key;
+use crate::machine::{Machine, weighted::WeightedMachine};
+
+///
+/// The Markov chain described by a weighted machine, as produced by
+///  [explore].
+///
+/// States are numbered in the order they are first reached, the start
+///  state being `0`, and every row of `transitions` lists the successors
+///  of a state along with the probability of moving to them. States
+///  without any edge of positive weight loop back to themselves.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chain<T> {
+    pub states: Vec<T>,
+    pub transitions: Vec<Vec<(usize, f64)>>,
+}
+
+impl<T> Chain<T> {
+    ///
+    /// Long-run fraction of time spent in each state when starting from
+    ///  the start state.
+    ///
+    /// Computed by power iteration on the lazy chain, which has the same
+    ///  stationary distribution but is guaranteed not to oscillate.
+    ///
+    pub fn stationary_distribution(&self) -> Vec<f64> {
+        let n = self.states.len();
+        let mut distribution = vec![0.0; n];
+        distribution[0] = 1.0;
+
+        for _ in 0..100_000 {
+            let mut next: Vec<f64> = distribution.iter().map(|p| p / 2.0).collect();
+            for (state, successors) in self.transitions.iter().enumerate() {
+                for &(target, probability) in successors {
+                    next[target] += distribution[state] * probability / 2.0;
+                }
+            }
+
+            let change: f64 = next
+                .iter()
+                .zip(&distribution)
+                .map(|(a, b)| if a > b { a - b } else { b - a })
+                .sum();
+            distribution = next;
+
+            if change < 1e-12 {
+                break;
+            }
+        }
+
+        distribution
+    }
+
+    ///
+    /// Expected number of transitions from the start state until a state
+    ///  satisfying `is_final` is reached.
+    ///
+    /// This is infinite when there is a chance of never reaching one.
+    ///
+    pub fn expected_steps(&self, mut is_final: impl FnMut(&T) -> bool) -> f64 {
+        let n = self.states.len();
+        let finals: Vec<bool> = self.states.iter().map(&mut is_final).collect();
+
+        // A final state is reached with probability one unless the chain can
+        //  wander off to a state from which no final state is reachable.
+        let reaches = self.backwards(&finals, finals.clone());
+        let doomed = self.backwards(&finals, reaches.iter().map(|r| !r).collect());
+
+        if doomed[0] {
+            return f64::INFINITY;
+        }
+        if finals[0] {
+            return 0.0;
+        }
+
+        // Solve h(s) = 1 + sum p(s, t) h(t) over the non-final states.
+        let unknowns: Vec<usize> = (0..n)
+            .filter(|&state| !finals[state] && !doomed[state])
+            .collect();
+        let index = |state: usize| unknowns.iter().position(|&s| s == state);
+        let m = unknowns.len();
+
+        let mut system = vec![vec![0.0; m + 1]; m];
+        for (row, &state) in unknowns.iter().enumerate() {
+            system[row][row] += 1.0;
+            system[row][m] = 1.0;
+            for &(target, probability) in &self.transitions[state] {
+                if let Some(column) = index(target) {
+                    system[row][column] -= probability;
+                }
+            }
+        }
+
+        solve(&mut system)[index(0).expect("start state is not final")]
+    }
+
+    // Grow a set of states backwards along transitions, without passing
+    //  through final states.
+    fn backwards(&self, finals: &[bool], mut set: Vec<bool>) -> Vec<bool> {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for state in 0..set.len() {
+                if !finals[state]
+                    && !set[state]
+                    && self.transitions[state]
+                        .iter()
+                        .any(|&(target, _)| set[target])
+                {
+                    set[state] = true;
+                    changed = true;
+                }
+            }
+        }
+
+        set
+    }
+}
+
+// Gaussian elimination with partial pivoting on an augmented matrix.
+fn solve(system: &mut [Vec<f64>]) -> Vec<f64> {
+    let m = system.len();
+    let abs = |x: f64| if x < 0.0 { -x } else { x };
+
+    for column in 0..m {
+        let pivot = (column..m)
+            .max_by(|&a, &b| abs(system[a][column]).total_cmp(&abs(system[b][column])))
+            .expect("column is in range");
+        system.swap(column, pivot);
+
+        let pivot = system[column].clone();
+        for (row, equation) in system.iter_mut().enumerate() {
+            if row != column {
+                let factor = equation[column] / pivot[column];
+                for (value, reduced) in equation.iter_mut().zip(&pivot).skip(column) {
+                    *value -= factor * reduced;
+                }
+            }
+        }
+    }
+
+    (0..m)
+        .map(|row| system[row][m] / system[row][row])
+        .collect()
+}
+
+///
+/// Explore every reachable configuration of a weighted machine, building
+///  its Markov chain.
+///
+/// Configurations are identified with `key`, as in [compile_by_key]. The
+///  key needs to tell apart every configuration with different behaviour:
+///  for machines whose state determines their future, such as those built
+///  with `machine!`, the state will do, but machines keeping state hidden,
+///  such as those built with `machine_chain!`, need a key that sees it.
+///  Configurations which the key does not tell apart are merged.
+///
+/// Returns `None` if more than `limit` configurations are found.
+///
+pub fn explore<T, M, K, F>(machine: &M, mut key: F, limit: usize) -> Option<Chain<T>>
+where
+    M: WeightedMachine<T> + Clone,
+    M::Transition: Clone,
+    K: PartialEq,
+    F: FnMut(&mut M) -> K,
+{
+    let table = compile_by_key(
+        &WithWeights(machine.clone()),
+        |weighted| key(&mut weighted.0),
+        limit,
+    )?;
+
+    let transitions = (0..table.len())
+        .map(|state| {
+            let outgoing = table.outgoing(state);
+            let total: f64 = outgoing
+                .iter()
+                .map(|((weight, _), _)| weight)
+                .filter(|weight| **weight > 0.0)
+                .sum();
+
+            let mut successors: Vec<(usize, f64)> = Vec::new();
+            for &((weight, _), target) in outgoing.iter().filter(|((w, _), _)| *w > 0.0) {
+                match successors.iter_mut().find(|(t, _)| *t == target) {
+                    Some((_, probability)) => *probability += weight / total,
+                    None => successors.push((target, weight / total)),
+                }
+            }
+
+            if successors.is_empty() {
+                successors.push((state, 1.0));
+            }
+            successors
+        })
+        .collect();
+
+    Some(Chain {
+        states: table.states,
+        transitions,
+    })
+}
+
+// Offers every edge along with its weight, so that the weights end up
+//  in the compiled table.
+#[derive(Clone)]
+struct WithWeights<M>(M);
+
+impl<T, M: WeightedMachine<T>> Machine<T> for WithWeights<M> {
+    type Transition = (f64, M::Transition);
+
+    fn edges(&self) -> impl Iterator<Item = (f64, M::Transition)> {
+        self.0.edges().map(|edge| (self.0.weight(&edge), edge))
+    }
+
+    fn state(&mut self) -> T {
+        self.0.state()
+    }
+
+    fn traverse(&mut self, (_, edge): &(f64, M::Transition)) {
+        self.0.traverse(edge);
+    }
+}
+
+///
+/// Stationary distribution of a weighted machine, see
+///  [Chain::stationary_distribution], with configurations identified by
+///  `key` as in [explore].
+///
+/// Returns `None` if more than `limit` configurations are found.
+///
+pub fn stationary_distribution<T, M, K, F>(
+    machine: &M,
+    key: F,
+    limit: usize,
+) -> Option<Vec<(T, f64)>>
+where
+    M: WeightedMachine<T> + Clone,
+    M::Transition: Clone,
+    K: PartialEq,
+    F: FnMut(&mut M) -> K,
+{
+    let chain = explore(machine, key, limit)?;
+    let distribution = chain.stationary_distribution();
+
+    Some(chain.states.into_iter().zip(distribution).collect())
+}
+
+///
+/// Expected number of transitions until a chainable machine produces
+///  its result, see [Chain::expected_steps], with configurations
+///  identified by `key` as in [explore].
+///
+/// Returns `None` if more than `limit` configurations are found.
+///
+pub fn expected_steps_to_final<T, M, K, F>(machine: &M, key: F, limit: usize) -> Option<f64>
+where
+    M: WeightedMachine<Option<T>> + Clone,
+    M::Transition: Clone,
+    K: PartialEq,
+    F: FnMut(&mut M) -> K,
+{
+    Some(explore(machine, key, limit)?.expected_steps(Option::is_some))
+}
@@ -0,0 +1,289 @@
+use alloc::{vec, vec::Vec};
+
+use crate::machine::{Machine, table::TableMachine};
+
+///
+/// An owned transition table, as produced by exploring a machine with
+///  [compile] or [compile_by_key].
+///
+/// States are numbered in the order they are first reached by a
+///  breadth-first traversal from the start state (which is always `0`),
+///  so two tables built from machines with the same behaviour can be
+///  compared directly once they have been [minimized](Table::minimize).
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table<E, T> {
+    pub states: Vec<T>,
+    pub offsets: Vec<usize>,
+    pub edges: Vec<(E, usize)>,
+}
+
+impl<E, T> Table<E, T> {
+    ///
+    /// Number of states in the table.
+    ///
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    ///
+    /// The edges out of a state, along with their targets.
+    ///
+    pub fn outgoing(&self, state: usize) -> &[(E, usize)] {
+        &self.edges[self.offsets[state]..self.offsets[state + 1]]
+    }
+
+    ///
+    /// Get a machine starting in the initial state of this table.
+    ///
+    pub fn machine(&self) -> TableMachine<'_, E, T> {
+        TableMachine::new(&self.states, &self.offsets, &self.edges, 0)
+    }
+}
+
+impl<E: Clone + PartialEq, T: Clone + PartialEq> Table<E, T> {
+    ///
+    /// Merge all states that cannot be told apart by their state, their
+    ///  edges, or the states they transition to, using Hopcroft's
+    ///  partition refinement algorithm.
+    ///
+    pub fn minimize(&self) -> Table<E, T> {
+        let n = self.len();
+
+        // Index the distinct transitions so that they can be used as an alphabet.
+        let mut alphabet: Vec<E> = Vec::new();
+        let mut delta: Vec<Vec<(usize, usize)>> = Vec::with_capacity(n);
+        for state in 0..n {
+            let mut row: Vec<(usize, usize)> = self
+                .outgoing(state)
+                .iter()
+                .map(|(edge, target)| (symbol(&mut alphabet, edge), *target))
+                .collect();
+            row.sort_unstable();
+            delta.push(row);
+        }
+
+        let mut inverse = vec![vec![Vec::new(); n]; alphabet.len()];
+        for (source, row) in delta.iter().enumerate() {
+            for &(a, target) in row {
+                inverse[a][target].push(source);
+            }
+        }
+
+        // Initial partition: states with the same value and the same set of edges.
+        let mut blocks: Vec<Vec<usize>> = Vec::new();
+        let mut block_of = vec![0; n];
+        for state in 0..n {
+            let existing = blocks.iter().position(|block| {
+                let other = block[0];
+                self.states[other] == self.states[state]
+                    && delta[other]
+                        .iter()
+                        .map(|(a, _)| a)
+                        .eq(delta[state].iter().map(|(a, _)| a))
+            });
+            match existing {
+                Some(b) => {
+                    blocks[b].push(state);
+                    block_of[state] = b;
+                }
+                None => {
+                    block_of[state] = blocks.len();
+                    blocks.push(vec![state]);
+                }
+            }
+        }
+
+        let mut worklist: Vec<(usize, usize)> = (0..blocks.len())
+            .flat_map(|b| (0..alphabet.len()).map(move |a| (b, a)))
+            .collect();
+
+        while let Some((splitter, a)) = worklist.pop() {
+            let mut marked = vec![false; n];
+            let mut touched = Vec::new();
+            for &target in &blocks[splitter] {
+                for &source in &inverse[a][target] {
+                    if !marked[source] {
+                        marked[source] = true;
+                        touched.push(block_of[source]);
+                    }
+                }
+            }
+
+            touched.sort_unstable();
+            touched.dedup();
+
+            for b in touched {
+                let (inside, outside): (Vec<usize>, Vec<usize>) =
+                    blocks[b].iter().partition(|&&s| marked[s]);
+
+                if outside.is_empty() {
+                    continue;
+                }
+
+                let new = blocks.len();
+                let (kept, moved) = if inside.len() <= outside.len() {
+                    (outside, inside)
+                } else {
+                    (inside, outside)
+                };
+                for &s in &moved {
+                    block_of[s] = new;
+                }
+                blocks[b] = kept;
+                blocks.push(moved);
+
+                for a in 0..alphabet.len() {
+                    if worklist.contains(&(b, a)) {
+                        worklist.push((new, a));
+                    } else {
+                        worklist.push(if blocks[b].len() <= blocks[new].len() {
+                            (b, a)
+                        } else {
+                            (new, a)
+                        });
+                    }
+                }
+            }
+        }
+
+        renumber(self, &block_of)
+    }
+}
+
+fn symbol<E: Clone + PartialEq>(alphabet: &mut Vec<E>, edge: &E) -> usize {
+    match alphabet.iter().position(|e| e == edge) {
+        Some(i) => i,
+        None => {
+            alphabet.push(edge.clone());
+            alphabet.len() - 1
+        }
+    }
+}
+
+///
+/// Rebuild a table in canonical (breadth-first) order, merging states
+///  that share a class in `class_of`.
+///
+fn renumber<E: Clone, T: Clone>(table: &Table<E, T>, class_of: &[usize]) -> Table<E, T> {
+    let mut index: Vec<Option<usize>> = vec![None; table.len()];
+    let mut representatives = vec![0];
+    index[class_of[0]] = Some(0);
+
+    let mut result = Table {
+        states: Vec::new(),
+        offsets: vec![0],
+        edges: Vec::new(),
+    };
+
+    let mut next = 0;
+    while next < representatives.len() {
+        let state = representatives[next];
+        result.states.push(table.states[state].clone());
+
+        for (edge, target) in table.outgoing(state) {
+            let class = class_of[*target];
+            let i = *index[class].get_or_insert_with(|| {
+                representatives.push(*target);
+                representatives.len() - 1
+            });
+            result.edges.push((edge.clone(), i));
+        }
+
+        result.offsets.push(result.edges.len());
+        next += 1;
+    }
+
+    result
+}
+
+///
+/// Explore every reachable configuration of a machine, building its
+///  transition table.
+///
+/// Configurations are identified by their state together with their
+///  edges, which is exact for machines whose state determines their
+///  future behaviour. For other machines, see [compile_by_key].
+///
+/// Returns `None` if more than `limit` configurations are found.
+///
+pub fn compile<T, M>(machine: &M, limit: usize) -> Option<Table<M::Transition, T>>
+where
+    M: Machine<T> + Clone,
+    M::Transition: Clone + PartialEq,
+    T: Clone + PartialEq,
+{
+    compile_by_key(
+        machine,
+        |m| (m.state(), m.edges().collect::<Vec<_>>()),
+        limit,
+    )
+}
+
+///
+/// Same as [compile], identifying configurations with `key`.
+///
+/// The key only needs to tell apart configurations with different
+///  behaviour -- configurations which turn out to be equivalent can
+///  be merged afterwards with [Table::minimize].
+///
+pub fn compile_by_key<T, M, K, F>(
+    machine: &M,
+    mut key: F,
+    limit: usize,
+) -> Option<Table<M::Transition, T>>
+where
+    M: Machine<T> + Clone,
+    M::Transition: Clone,
+    K: PartialEq,
+    F: FnMut(&mut M) -> K,
+{
+    if limit == 0 {
+        return None;
+    }
+
+    let mut start = machine.clone();
+    let mut keys = vec![key(&mut start)];
+    let mut configurations = vec![start];
+
+    let mut table = Table {
+        states: Vec::new(),
+        offsets: vec![0],
+        edges: Vec::new(),
+    };
+
+    let mut next = 0;
+    while next < configurations.len() {
+        let state = configurations[next].state();
+        table.states.push(state);
+
+        for edge in configurations[next].edges().collect::<Vec<_>>() {
+            let mut successor = configurations[next].clone();
+            successor.traverse(&edge);
+            let successor_key = key(&mut successor);
+
+            let target = match keys.iter().position(|k| *k == successor_key) {
+                Some(i) => i,
+                None => {
+                    if configurations.len() == limit {
+                        return None;
+                    }
+                    keys.push(successor_key);
+                    configurations.push(successor);
+                    configurations.len() - 1
+                }
+            };
+
+            table.edges.push((edge, target));
+        }
+
+        table.offsets.push(table.edges.len());
+        next += 1;
+    }
+
+    Some(table)
+}
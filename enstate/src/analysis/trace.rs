@@ -0,0 +1,107 @@
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::{self, Debug, Display};
+use core::marker::PhantomData;
+
+use crate::machine::Machine;
+
+///
+/// A recorded step: the edge that was traversed, and the state and edges
+///  of the machine right after it.
+///
+/// The initial step of a trace has no edge.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub edge: Option<String>,
+    pub state: String,
+    pub edges: Vec<String>,
+}
+
+///
+/// Machine wrapper recording every traversal, for comparing the behaviour
+///  of a machine against a snapshot.
+///
+/// Edges and states are recorded through their `Debug` representation, and
+///  the trace is rendered as text with one block per step through
+///  `Display`:
+///
+/// ```text
+/// state: None
+/// edges: [Increment, Decrement]
+///
+/// on Increment
+/// state: None
+/// edges: [Increment, Decrement]
+/// ```
+///
+#[derive(Clone)]
+pub struct Tracer<T, M> {
+    pub(crate) t: PhantomData<T>,
+    pub(crate) machine: M,
+    pub(crate) steps: Vec<Step>,
+}
+
+impl<T: Debug, M: Machine<T, Transition: Debug>> Tracer<T, M> {
+    pub fn new(machine: M) -> Tracer<T, M> {
+        let mut tracer = Tracer {
+            t: PhantomData,
+            machine,
+            steps: Vec::new(),
+        };
+        tracer.record(None);
+        tracer
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn into_inner(self) -> M {
+        self.machine
+    }
+
+    fn record(&mut self, edge: Option<String>) {
+        let state = format!("{:?}", self.machine.state());
+        let edges = self
+            .machine
+            .edges()
+            .map(|edge| format!("{edge:?}"))
+            .collect();
+
+        self.steps.push(Step { edge, state, edges });
+    }
+}
+
+impl<T: Debug, M: Machine<T, Transition: Debug>> Machine<T> for Tracer<T, M> {
+    type Transition = M::Transition;
+
+    fn edges(&self) -> impl Iterator<Item = M::Transition> {
+        self.machine.edges()
+    }
+
+    fn state(&mut self) -> T {
+        self.machine.state()
+    }
+
+    fn traverse(&mut self, edge: &M::Transition) {
+        self.machine.traverse(edge);
+        self.record(Some(format!("{edge:?}")));
+    }
+}
+
+impl<T, M> Display for Tracer<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            if let Some(edge) = &step.edge {
+                writeln!(f, "on {edge}")?;
+            }
+            writeln!(f, "state: {}", step.state)?;
+            writeln!(f, "edges: [{}]", step.edges.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
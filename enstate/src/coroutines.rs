@@ -4,36 +4,64 @@ use core::{
     pin::pin,
 };
 
-use crate::machine::Machine;
+use crate::machine::{Machine, chained::Progress};
+
+#[cfg(feature = "alloc")]
+pub mod asynchronous;
+pub mod choices;
+
+use choices::Choices;
 
 ///
 /// Trait alias used to facilitate constructing machines with Rust coroutines
 ///  on nightly.
 ///
+/// The coroutine is first resumed with `None` to run it up to its first
+///  `yield`, and from then on is only resumed with `Some` of the action
+///  that was chosen.
+///
 /// Best used together with the machine!() macro.
 ///
 pub trait StateMachine<Action: 'static, State> =
-    Coroutine<Action, Yield = (State, &'static [Action]), Return = !>;
+    Coroutine<Option<Action>, Yield = (State, Choices<Action>), Return = !>;
 
 pub trait ChainStateMachine<Action: 'static, Result> =
-    Coroutine<Action, Yield = &'static [Action], Return = Result>;
+    Coroutine<Option<Action>, Yield = Choices<Action>, Return = Result>;
+
+pub trait ProgressStateMachine<Action: 'static, State, Result> =
+    Coroutine<Option<Action>, Yield = (State, Choices<Action>), Return = Result>;
+
+///
+/// Unwrap the action a coroutine machine was resumed with after a `yield`.
+///
+/// Since coroutines are only resumed with `None` to start them, this
+///  always succeeds for the value of a `yield` expression.
+///
+#[inline]
+pub fn resumed<A>(action: Option<A>) -> A {
+    match action {
+        Some(action) => action,
+        None => unreachable!("coroutine machines are only started with `None`"),
+    }
+}
 
 ///
 /// Struct used to treat a coroutine state machine as a machine.
 ///
+#[derive(Clone)]
 pub struct AsMachine<A, S, M> {
     pub a: PhantomData<A>,
     pub state: S,
     pub machine: M,
 }
 
-impl<State: Clone, Action: Clone + Default, M: StateMachine<Action, State> + Unpin>
-    AsMachine<Action, CoroutineState<(State, &'static [Action]), !>, M>
+impl<State: Clone, Action: Clone + 'static, M: StateMachine<Action, State> + Unpin>
+    AsMachine<Action, CoroutineState<(State, Choices<Action>), !>, M>
 {
-    pub fn new(machine: M) -> AsMachine<Action, CoroutineState<(State, &'static [Action]), !>, M> {
+    pub fn new(machine: M) -> AsMachine<Action, CoroutineState<(State, Choices<Action>), !>, M> {
         let mut machine = machine;
         let pin = pin!(&mut machine);
-        let initial = pin.resume(Action::default());
+        let initial = pin.resume(None);
 
         AsMachine {
             a: PhantomData,
@@ -43,14 +71,14 @@ impl<State: Clone, Action: Clone + Default, M: StateMachine<Action, State> + Unp
     }
 }
 
-impl<State: Clone, Action: Clone + Default, M: StateMachine<Action, State> + Unpin> Machine<State>
-    for AsMachine<Action, CoroutineState<(State, &'static [Action]), !>, M>
+impl<State: Clone, Action: Clone + 'static, M: StateMachine<Action, State> + Unpin> Machine<State>
+    for AsMachine<Action, CoroutineState<(State, Choices<Action>), !>, M>
 {
     type Transition = Action;
 
     fn edges(&self) -> impl Iterator<Item = Self::Transition> {
         let CoroutineState::Yielded(result) = &self.state;
-        result.1.into_iter().map(|x| x.clone())
+        result.1.iter().cloned()
     }
 
     fn state(&mut self) -> State {
@@ -60,21 +88,22 @@ impl<State: Clone, Action: Clone + Default, M: StateMachine<Action, State> + Unp
     }
 
     fn traverse(&mut self, edge: &Self::Transition) {
-        self.state = pin!(&mut self.machine).resume(edge.clone());
+        self.state = pin!(&mut self.machine).resume(Some(edge.clone()));
     }
 }
 
+#[derive(Clone)]
 pub struct AsChainMachine<A: 'static, R, M> {
     pub a: PhantomData<A>,
-    pub state: CoroutineState<&'static [A], R>,
+    pub state: CoroutineState<Choices<A>, R>,
     pub machine: M,
 }
 
-impl<A: Clone + Default, R, M: ChainStateMachine<A, R> + Unpin> AsChainMachine<A, R, M> {
+impl<A: Clone, R, M: ChainStateMachine<A, R> + Unpin> AsChainMachine<A, R, M> {
     pub fn new(machine: M) -> AsChainMachine<A, R, M> {
         let mut machine = machine;
         let pin = pin!(&mut machine);
-        let initial = pin.resume(A::default());
+        let initial = pin.resume(None);
 
         AsChainMachine {
             a: PhantomData,
@@ -84,20 +113,26 @@ impl<A: Clone + Default, R, M: ChainStateMachine<A, R> + Unpin> AsChainMachine<A
     }
 }
 
-impl<A: Clone + Default, R: Clone, M: ChainStateMachine<A, R> + Unpin> Machine<Option<R>>
+impl<A: Clone, R: Clone, M: ChainStateMachine<A, R> + Unpin> Machine<Option<R>>
     for AsChainMachine<A, R, M>
 {
     type Transition = A;
 
     fn edges(&self) -> impl Iterator<Item = A> {
-        match &self.state {
-            CoroutineState::Yielded(actions) => actions.iter().cloned(),
-            CoroutineState::Complete(_) => [].iter().cloned(),
-        }
+        let actions = match &self.state {
+            CoroutineState::Yielded(actions) => Some(actions),
+            CoroutineState::Complete(_) => None,
+        };
+
+        actions
+            .into_iter()
+            .flat_map(|actions| actions.iter().cloned())
     }
 
     fn traverse(&mut self, edge: &A) {
-        self.state = pin!(&mut self.machine).resume(edge.clone());
+        if let CoroutineState::Yielded(_) = self.state {
+            self.state = pin!(&mut self.machine).resume(Some(edge.clone()));
+        }
     }
 
     fn state(&mut self) -> Option<R> {
@@ -107,3 +142,58 @@ impl<A: Clone + Default, R: Clone, M: ChainStateMachine<A, R> + Unpin> Machine<O
         }
     }
 }
+
+///
+/// Struct used to treat a coroutine which yields its state until it
+///  returns a result as a chainable machine.
+///
+#[derive(Clone)]
+pub struct AsProgressMachine<A: 'static, S, R, M> {
+    pub a: PhantomData<A>,
+    pub state: CoroutineState<(S, Choices<A>), R>,
+    pub machine: M,
+}
+
+impl<A: Clone, S, R, M: ProgressStateMachine<A, S, R> + Unpin> AsProgressMachine<A, S, R, M> {
+    pub fn new(machine: M) -> AsProgressMachine<A, S, R, M> {
+        let mut machine = machine;
+        let pin = pin!(&mut machine);
+        let initial = pin.resume(None);
+
+        AsProgressMachine {
+            a: PhantomData,
+            state: initial,
+            machine,
+        }
+    }
+}
+
+impl<A: Clone, S: Clone, R: Clone, M: ProgressStateMachine<A, S, R> + Unpin> Machine<Progress<S, R>>
+    for AsProgressMachine<A, S, R, M>
+{
+    type Transition = A;
+
+    fn edges(&self) -> impl Iterator<Item = A> {
+        let actions = match &self.state {
+            CoroutineState::Yielded((_, actions)) => Some(actions),
+            CoroutineState::Complete(_) => None,
+        };
+
+        actions
+            .into_iter()
+            .flat_map(|actions| actions.iter().cloned())
+    }
+
+    fn traverse(&mut self, edge: &A) {
+        if let CoroutineState::Yielded(_) = self.state {
+            self.state = pin!(&mut self.machine).resume(Some(edge.clone()));
+        }
+    }
+
+    fn state(&mut self) -> Progress<S, R> {
+        match &self.state {
+            CoroutineState::Yielded((state, _)) => Progress::InProgress(state.clone()),
+            CoroutineState::Complete(result) => Progress::Done(result.clone()),
+        }
+    }
+}
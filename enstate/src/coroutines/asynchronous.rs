@@ -0,0 +1,182 @@
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+use core::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use super::choices::Choices;
+use crate::machine::Machine;
+
+///
+/// State of an async machine, which is `Pending` while its body is
+///  awaiting something other than a choice, and `Finished` once its body
+///  has returned.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AsyncState<S> {
+    Ready(S),
+    Pending,
+    Finished,
+}
+
+struct Slot<A: 'static, S> {
+    offered: Option<(S, Choices<A>)>,
+    chosen: Option<A>,
+}
+
+///
+/// Handle used by the body of an async machine to offer choices.
+///
+/// Best used through `choose!` in the async_machine!() macro.
+///
+pub struct Chooser<A: 'static, S> {
+    slot: Rc<RefCell<Slot<A, S>>>,
+}
+
+impl<A, S> Chooser<A, S> {
+    ///
+    /// Offer a set of choices along with the current state, completing
+    ///  with the action that was chosen.
+    ///
+    pub fn choose(&self, state: S, choices: Choices<A>) -> Choose<'_, A, S> {
+        Choose {
+            chooser: self,
+            offer: Some((state, choices)),
+        }
+    }
+}
+
+///
+/// Future returned by [Chooser::choose].
+///
+pub struct Choose<'a, A: 'static, S> {
+    chooser: &'a Chooser<A, S>,
+    offer: Option<(S, Choices<A>)>,
+}
+
+impl<A, S> Unpin for Choose<'_, A, S> {}
+
+impl<A, S> Future for Choose<'_, A, S> {
+    type Output = A;
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<A> {
+        let mut slot = self.chooser.slot.borrow_mut();
+
+        if let Some(offer) = self.offer.take() {
+            slot.offered = Some(offer);
+            return Poll::Pending;
+        }
+
+        match slot.chosen.take() {
+            Some(action) => Poll::Ready(action),
+            None => Poll::Pending,
+        }
+    }
+}
+
+///
+/// Struct used to treat an async block as a machine.
+///
+/// Whenever a choice is made, the body is polled until it either reaches
+///  the next choice, or awaits something else, in which case the machine
+///  is [AsyncState::Pending] until it is driven further with
+///  [poll](AsAsyncMachine::poll).
+///
+/// Since `traverse` has no context of its own, it polls the body with the
+///  waker from the last call to `poll`, or a no-op waker before then.
+///
+pub struct AsAsyncMachine<A: 'static, S, F> {
+    slot: Rc<RefCell<Slot<A, S>>>,
+    future: Pin<Box<F>>,
+    waker: Option<Waker>,
+    finished: bool,
+}
+
+impl<A, S, F: Future> AsAsyncMachine<A, S, F> {
+    pub fn new(body: impl FnOnce(Chooser<A, S>) -> F) -> AsAsyncMachine<A, S, F> {
+        let slot = Rc::new(RefCell::new(Slot {
+            offered: None,
+            chosen: None,
+        }));
+
+        let future = Box::pin(body(Chooser { slot: slot.clone() }));
+
+        let mut machine = AsAsyncMachine {
+            slot,
+            future,
+            waker: None,
+            finished: false,
+        };
+        machine.drive(Waker::noop());
+        machine
+    }
+
+    ///
+    /// Drive the body of the machine, returning `Ready` once it is
+    ///  waiting for a choice or has finished.
+    ///
+    pub fn poll(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.waker = Some(cx.waker().clone());
+        self.drive(cx.waker());
+
+        if self.is_pending() {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        !self.finished && self.slot.borrow().offered.is_none()
+    }
+
+    fn drive(&mut self, waker: &Waker) {
+        if self.is_pending()
+            && self
+                .future
+                .as_mut()
+                .poll(&mut Context::from_waker(waker))
+                .is_ready()
+        {
+            self.finished = true;
+        }
+    }
+}
+
+impl<A: Clone, S: Clone, F: Future> Machine<AsyncState<S>> for AsAsyncMachine<A, S, F> {
+    type Transition = A;
+
+    fn edges(&self) -> impl Iterator<Item = A> {
+        let slot = self.slot.borrow();
+        let edges: Vec<A> = match &slot.offered {
+            Some((_, choices)) => choices.iter().cloned().collect(),
+            None => Vec::new(),
+        };
+
+        edges.into_iter()
+    }
+
+    fn state(&mut self) -> AsyncState<S> {
+        match &self.slot.borrow().offered {
+            Some((state, _)) => AsyncState::Ready(state.clone()),
+            None if self.finished => AsyncState::Finished,
+            None => AsyncState::Pending,
+        }
+    }
+
+    fn traverse(&mut self, edge: &A) {
+        {
+            let mut slot = self.slot.borrow_mut();
+            if slot.offered.take().is_none() {
+                return;
+            }
+            slot.chosen = Some(edge.clone());
+        }
+
+        let waker = self.waker.take();
+        self.drive(waker.as_ref().unwrap_or(Waker::noop()));
+        self.waker = waker;
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{iter::Flatten, slice};
+
+///
+/// Number of choices that can be stored in a [Choices] buffer before it
+///  needs to spill onto the heap (or panics, without the `alloc` feature).
+///
+pub const INLINE_CAPACITY: usize = 16;
+
+///
+/// The set of actions offered by a coroutine machine at a `choose!` point.
+///
+/// Fixed lists of constant choices are stored as a `&'static` slice at no
+///  cost. Choices which are only known at runtime are pushed into an inline
+///  buffer, which moves to a `Vec` if it overflows and the `alloc` feature
+///  is enabled.
+///
+#[derive(Clone, Debug)]
+pub enum Choices<A: 'static> {
+    Static(&'static [A]),
+    Inline {
+        len: usize,
+        items: [Option<A>; INLINE_CAPACITY],
+    },
+    #[cfg(feature = "alloc")]
+    Heap(Vec<A>),
+}
+
+impl<A> Choices<A> {
+    ///
+    /// Create an empty set of choices to build up at runtime.
+    ///
+    pub const fn new() -> Self {
+        Choices::Inline {
+            len: 0,
+            items: [const { None }; INLINE_CAPACITY],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Choices::Static(actions) => actions.len(),
+            Choices::Inline { len, .. } => *len,
+            #[cfg(feature = "alloc")]
+            Choices::Heap(actions) => actions.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///
+    /// Add a choice to the end of the list.
+    ///
+    /// # Panics
+    ///
+    /// Without the `alloc` feature, panics if more than [INLINE_CAPACITY]
+    ///  choices are offered at once.
+    ///
+    pub fn push(&mut self, action: A)
+    where
+        A: Clone,
+    {
+        match self {
+            Choices::Static(actions) => {
+                let mut choices = Choices::new();
+                choices.extend(actions.iter().cloned());
+                choices.push(action);
+                *self = choices;
+            }
+            Choices::Inline { len, items } if *len < INLINE_CAPACITY => {
+                items[*len] = Some(action);
+                *len += 1;
+            }
+            #[cfg(feature = "alloc")]
+            Choices::Inline { items, .. } => {
+                let mut actions: Vec<A> = items.iter_mut().flat_map(Option::take).collect();
+                actions.push(action);
+                *self = Choices::Heap(actions);
+            }
+            #[cfg(not(feature = "alloc"))]
+            Choices::Inline { .. } => {
+                panic!("more than {INLINE_CAPACITY} choices offered at once")
+            }
+            #[cfg(feature = "alloc")]
+            Choices::Heap(actions) => actions.push(action),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, A> {
+        let (actions, items): (&[A], &[Option<A>]) = match self {
+            Choices::Static(actions) => (actions, &[]),
+            Choices::Inline { len, items } => (&[], &items[..*len]),
+            #[cfg(feature = "alloc")]
+            Choices::Heap(actions) => (actions, &[]),
+        };
+
+        Iter {
+            actions: actions.iter(),
+            items: items.iter().flatten(),
+        }
+    }
+}
+
+impl<A> Default for Choices<A> {
+    fn default() -> Self {
+        Choices::new()
+    }
+}
+
+impl<A> From<&'static [A]> for Choices<A> {
+    fn from(actions: &'static [A]) -> Self {
+        Choices::Static(actions)
+    }
+}
+
+impl<A: Clone> Extend<A> for Choices<A> {
+    fn extend<I: IntoIterator<Item = A>>(&mut self, iter: I) {
+        for action in iter {
+            self.push(action);
+        }
+    }
+}
+
+impl<A: Clone> FromIterator<A> for Choices<A> {
+    fn from_iter<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        let mut choices = Choices::new();
+        choices.extend(iter);
+        choices
+    }
+}
+
+impl<'a, A> IntoIterator for &'a Choices<A> {
+    type Item = &'a A;
+    type IntoIter = Iter<'a, A>;
+
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+///
+/// Iterator over the actions in a [Choices].
+///
+pub struct Iter<'a, A> {
+    actions: slice::Iter<'a, A>,
+    items: Flatten<slice::Iter<'a, Option<A>>>,
+}
+
+impl<'a, A> Iterator for Iter<'a, A> {
+    type Item = &'a A;
+
+    fn next(&mut self) -> Option<&'a A> {
+        self.actions.next().or_else(|| self.items.next())
+    }
+}
@@ -1,11 +1,10 @@
-#![feature(
-    coroutines,
-    coroutine_trait,
-    trait_alias,
-    never_type,
-    exhaustive_patterns
-)]
+#![feature(coroutine_trait, trait_alias, never_type, exhaustive_patterns)]
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod analysis;
 pub mod coroutines;
 pub mod machine;
@@ -1,10 +1,30 @@
 use core::marker::PhantomData;
 
+#[cfg(feature = "alloc")]
+use boxed::BoxedMachine;
+use dependent::DependentMachine;
+use guarded::GuardedMachine;
 use mapped::{MappedMachine, MappedTransitionMachine};
+use scanned::ScannedMachine;
+use weighted::Weighted;
 use zipped::ZippedMachine;
 
+pub mod array;
+#[cfg(feature = "alloc")]
+pub mod boxed;
 pub mod chained;
+pub mod dependent;
+pub mod fallible;
+pub mod guarded;
+pub mod interruptible;
+#[cfg(feature = "alloc")]
+pub mod list;
 pub mod mapped;
+#[cfg(feature = "alloc")]
+pub mod saga;
+pub mod scanned;
+pub mod table;
+pub mod weighted;
 pub mod zipped;
 
 ///
@@ -70,6 +90,71 @@ pub trait Machine<T>: Sized {
         }
     }
 
+    ///
+    /// Only offer the edges allowed by a predicate on the current state,
+    ///  rejecting the others when traversed.
+    ///
+    #[inline]
+    fn guard<F>(mut self, f: F) -> GuardedMachine<T, Self, F>
+    where
+        F: Fn(&T, &Self::Transition) -> bool,
+    {
+        GuardedMachine {
+            state: self.state(),
+            machine: self,
+            f,
+        }
+    }
+
+    ///
+    /// Keep an accumulator alongside the state, which `f` updates from
+    ///  every accepted edge and the state reached through it.
+    ///
+    /// The state becomes a pair of the original state and the accumulator,
+    ///  which can be combined further with [Machine::map].
+    ///
+    #[inline]
+    fn scan<A, F>(self, init: A, f: F) -> ScannedMachine<T, Self, A, F>
+    where
+        F: FnMut(&mut A, &Self::Transition, &T),
+    {
+        ScannedMachine {
+            t: PhantomData,
+            machine: self,
+            acc: init,
+            f,
+        }
+    }
+
+    ///
+    /// Erase the type of a machine, so that it can be stored alongside
+    ///  other machines with the same state and transitions.
+    ///
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn boxed<'a>(self) -> BoxedMachine<'a, T, Self::Transition>
+    where
+        Self: 'a,
+    {
+        BoxedMachine::new(self)
+    }
+
+    ///
+    /// Attach a weight to every edge, turning the machine into a
+    ///  [WeightedMachine](weighted::WeightedMachine).
+    ///
+    #[inline]
+    fn weighted<F>(self, f: F) -> Weighted<T, Self, F>
+    where
+        F: Fn(&Self::Transition) -> f64,
+    {
+        Weighted {
+            t: PhantomData,
+            machine: self,
+            f,
+        }
+    }
+
     ///
     /// Combine two machines "horizontally", combinding their state with a function.
     ///
@@ -82,10 +167,9 @@ pub trait Machine<T>: Sized {
     ) -> impl Machine<W, Transition = E>
     where
         M2: Machine<U>,
-        // M2::Transition: PartialEq<Self::Transition>,
         Self::Transition: Into<E>,
         M2::Transition: Into<E>,
-        E: Clone,
+        E: Clone + PartialEq,
         E: TryInto<Self::Transition>,
         E: TryInto<M2::Transition>,
         G: FnMut(T, U) -> W,
@@ -107,7 +191,7 @@ pub trait Machine<T>: Sized {
     ) -> impl Machine<W, Transition = Self::Transition>
     where
         M2: Machine<U, Transition = Self::Transition>,
-        // M2::Transition: PartialEq<Self::Transition>,
+        Self::Transition: PartialEq,
         G: FnMut(T, U) -> W,
     {
         ZippedMachine {
@@ -118,4 +202,21 @@ pub trait Machine<T>: Sized {
             f,
         }
     }
+
+    ///
+    /// Combine a machine with a second one made from its state, which is
+    ///  remade by `g` whenever that state changes.
+    ///
+    /// `g` is also given the previous second machine, if any, so it can
+    ///  decide whether to keep it or start over.
+    ///
+    #[inline]
+    fn zip_dependent<M2, U, G, F, W>(self, g: G, f: F) -> DependentMachine<T, U, Self, M2, G, F>
+    where
+        M2: Machine<U, Transition = Self::Transition>,
+        G: FnMut(&T, Option<M2>) -> M2,
+        F: FnMut(T, U) -> W,
+    {
+        DependentMachine::new(self, g, f)
+    }
 }
@@ -0,0 +1,111 @@
+use core::marker::PhantomData;
+
+use super::Machine;
+
+///
+/// Run a fixed number of machines of the same type side by side, without
+///  nesting [ZippedMachine](super::zipped::ZippedMachine)s.
+///
+/// Edges are routed to a single machine by its index, or broadcast to
+///  every machine offering them.
+///
+#[inline]
+pub fn array_zip<T, M, const N: usize>(machines: [M; N]) -> ArrayMachine<T, M, N>
+where
+    M: Machine<T>,
+{
+    ArrayMachine {
+        t: PhantomData,
+        machines,
+    }
+}
+
+///
+/// Transitions of an [ArrayMachine].
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArrayTransition<E> {
+    Item(usize, E),
+    /// Pass the edge to every machine offering it, the others are left
+    ///  as they are.
+    Broadcast(E),
+}
+
+#[derive(Clone)]
+pub struct ArrayMachine<T, M, const N: usize> {
+    t: PhantomData<T>,
+    machines: [M; N],
+}
+
+impl<T, M, const N: usize> ArrayMachine<T, M, N> {
+    pub fn machines(&self) -> &[M; N] {
+        &self.machines
+    }
+}
+
+impl<T, M, const N: usize> Machine<[T; N]> for ArrayMachine<T, M, N>
+where
+    M: Machine<T, Transition: PartialEq>,
+{
+    type Transition = ArrayTransition<M::Transition>;
+
+    ///
+    /// Edges of every machine, by index, followed by a broadcast of every
+    ///  distinct edge offered by any of them.
+    ///
+    fn edges(&self) -> impl Iterator<Item = Self::Transition> {
+        let items = self.machines.iter().enumerate().flat_map(|(i, machine)| {
+            machine
+                .edges()
+                .map(move |edge| ArrayTransition::Item(i, edge))
+        });
+
+        let broadcasts = self
+            .machines
+            .iter()
+            .enumerate()
+            .flat_map(move |(i, machine)| {
+                machine
+                    .edges()
+                    .enumerate()
+                    .filter(move |(k, edge)| {
+                        // Only the first occurrence of an edge is broadcast.
+                        !self.machines[..i]
+                            .iter()
+                            .any(|earlier| earlier.edges().any(|e| e == *edge))
+                            && !machine.edges().take(*k).any(|e| e == *edge)
+                    })
+                    .map(|(_, edge)| ArrayTransition::Broadcast(edge))
+            });
+
+        items.chain(broadcasts)
+    }
+
+    fn state(&mut self) -> [T; N] {
+        let machines = &mut self.machines;
+        core::array::from_fn(|i| machines[i].state())
+    }
+
+    fn traverse(&mut self, edge: &Self::Transition) {
+        // Machines only see the edges they offer, so that a broadcast does
+        //  not reach machines which cannot take it.
+        let offers = |machine: &M, edge: &M::Transition| machine.edges().any(|e| e == *edge);
+
+        match edge {
+            ArrayTransition::Item(i, edge) => {
+                if let Some(machine) = self.machines.get_mut(*i)
+                    && offers(machine, edge)
+                {
+                    machine.traverse(edge);
+                }
+            }
+            ArrayTransition::Broadcast(edge) => {
+                for machine in &mut self.machines {
+                    if offers(machine, edge) {
+                        machine.traverse(edge);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use super::Machine;
+
+// Object safe counterpart of `Machine`, collecting edges eagerly.
+trait DynMachine<T, E> {
+    fn edges(&self) -> Vec<E>;
+    fn state(&mut self) -> T;
+    fn traverse(&mut self, edge: &E);
+}
+
+impl<T, M: Machine<T>> DynMachine<T, M::Transition> for M {
+    fn edges(&self) -> Vec<M::Transition> {
+        Machine::edges(self).collect()
+    }
+
+    fn state(&mut self) -> T {
+        Machine::state(self)
+    }
+
+    fn traverse(&mut self, edge: &M::Transition) {
+        Machine::traverse(self, edge)
+    }
+}
+
+///
+/// A machine behind a pointer, so that machines of different types with
+///  the same state and transitions can be stored together.
+///
+/// See [Machine::boxed].
+///
+pub struct BoxedMachine<'a, T, E> {
+    machine: Box<dyn DynMachine<T, E> + 'a>,
+}
+
+impl<'a, T, E> BoxedMachine<'a, T, E> {
+    pub fn new<M: Machine<T, Transition = E> + 'a>(machine: M) -> BoxedMachine<'a, T, E> {
+        BoxedMachine {
+            machine: Box::new(machine),
+        }
+    }
+}
+
+impl<T, E> Machine<T> for BoxedMachine<'_, T, E> {
+    type Transition = E;
+
+    fn edges(&self) -> impl Iterator<Item = E> {
+        self.machine.edges().into_iter()
+    }
+
+    fn state(&mut self) -> T {
+        self.machine.state()
+    }
+
+    fn traverse(&mut self, edge: &E) {
+        self.machine.traverse(edge);
+    }
+}
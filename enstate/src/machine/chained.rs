@@ -1,7 +1,37 @@
-use core::{iter::empty, marker::PhantomData};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{iter::empty, marker::PhantomData, ops::ControlFlow};
 
 use super::Machine;
 
+///
+/// Trait for the state of a machine that eventually finishes, which
+///  allows it to be chained with other machines.
+///
+/// While the first machine in a chain is running, the state of the chain
+///  is given by [resolve](Final::resolve), and once it produces an output
+///  the chain moves on to the next machine.
+///
+/// Final states which do not produce an output, such as `Err` for
+///  `Result<Option<T>, E>`, end the chain early.
+///
+pub trait Final: Sized {
+    type Output;
+
+    ///
+    /// The same kind of state, for a chain which finishes with an `X`.
+    ///
+    type Chained<X>;
+
+    fn is_final(&self) -> bool;
+
+    ///
+    /// Either the output of a finished machine, or the state a chain
+    ///  starting with this machine is in.
+    ///
+    fn resolve<X>(self) -> Result<Self::Output, Self::Chained<X>>;
+}
+
 ///
 /// Trait for machines that can be chainable, usually of the
 ///  form Machine<Option<T>>.
@@ -14,6 +44,101 @@ pub trait Chainable<T>: Machine<T> {
         M2: Machine<Self::Result<U>, Transition = Self::Transition>;
 }
 
+///
+/// State of a chainable machine which exposes its state while it is
+///  still running, rather than just `None`.
+///
+/// Only `Done` states are considered final when chaining.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Progress<S, R> {
+    InProgress(S),
+    Done(R),
+}
+
+impl<S, R> Progress<S, R> {
+    pub fn is_done(&self) -> bool {
+        matches!(self, Progress::Done(_))
+    }
+
+    ///
+    /// Get the final result, if the machine is done.
+    ///
+    pub fn result(self) -> Option<R> {
+        match self {
+            Progress::InProgress(_) => None,
+            Progress::Done(result) => Some(result),
+        }
+    }
+}
+
+impl<T> Final for Option<T> {
+    type Output = T;
+    type Chained<X> = Option<X>;
+
+    fn is_final(&self) -> bool {
+        self.is_some()
+    }
+
+    fn resolve<X>(self) -> Result<T, Option<X>> {
+        self.ok_or(None)
+    }
+}
+
+impl<S, R> Final for Progress<S, R> {
+    type Output = R;
+    type Chained<X> = Progress<S, X>;
+
+    fn is_final(&self) -> bool {
+        self.is_done()
+    }
+
+    fn resolve<X>(self) -> Result<R, Progress<S, X>> {
+        match self {
+            Progress::InProgress(state) => Err(Progress::InProgress(state)),
+            Progress::Done(result) => Ok(result),
+        }
+    }
+}
+
+impl<B, C> Final for ControlFlow<B, C> {
+    type Output = B;
+    type Chained<X> = ControlFlow<X, C>;
+
+    fn is_final(&self) -> bool {
+        self.is_break()
+    }
+
+    fn resolve<X>(self) -> Result<B, ControlFlow<X, C>> {
+        match self {
+            ControlFlow::Continue(state) => Err(ControlFlow::Continue(state)),
+            ControlFlow::Break(result) => Ok(result),
+        }
+    }
+}
+
+///
+/// Machines which can fail are written as `Machine<Result<Option<T>, E>>`,
+///  which is `Ok(None)` while running, and where an `Err` skips the rest
+///  of a chain.
+///
+impl<T, E> Final for Result<Option<T>, E> {
+    type Output = T;
+    type Chained<X> = Result<Option<X>, E>;
+
+    fn is_final(&self) -> bool {
+        !matches!(self, Ok(None))
+    }
+
+    fn resolve<X>(self) -> Result<T, Result<Option<X>, E>> {
+        match self {
+            Ok(Some(result)) => Ok(result),
+            Ok(None) => Err(Ok(None)),
+            Err(error) => Err(Err(error)),
+        }
+    }
+}
+
 pub trait JoinMachine<M1, T> {
     fn join(self) -> impl Machine<T>;
 }
@@ -30,11 +155,39 @@ impl<
     F: FnMut(A) -> M2,
 > FlatMappable<Option<A>, F, Option<B>> for M1
 {
-    fn flat_map(self, mut f: F) -> impl Machine<Option<B>, Transition = Self::Transition> {
-        self.map(move |x| x.map(&mut f)).join()
+    fn flat_map(self, f: F) -> impl Machine<Option<B>, Transition = Self::Transition> {
+        JoinedMachine::new(self, f)
+    }
+}
+
+///
+/// Same as [JoinMachine], for machines with any [Final] state whose
+///  output is the machine to run next.
+///
+pub trait JoinFinal<S: Final, X> {
+    fn join_final(self) -> impl Machine<S::Chained<X>>;
+}
+
+///
+/// Same as [FlatMappable], for machines with any [Final] state.
+///
+pub trait FlatMapFinal<A: Final, F, X>: Machine<A> {
+    fn flat_map_final(self, f: F) -> impl Machine<A::Chained<X>, Transition = Self::Transition>;
+}
+
+impl<A, X, M1, M2, F> FlatMapFinal<A, F, X> for M1
+where
+    A: Final,
+    M1: Machine<A>,
+    M2: Machine<A::Chained<X>, Transition = M1::Transition>,
+    F: FnMut(A::Output) -> M2,
+{
+    fn flat_map_final(self, f: F) -> impl Machine<A::Chained<X>, Transition = Self::Transition> {
+        JoinedMachine::new(self, f)
     }
 }
 
+#[derive(Clone)]
 pub enum JoinedMachineState<M1, M2> {
     First(M1),
     Second(M2),
@@ -56,60 +209,107 @@ impl<I1: Iterator, I2: Iterator<Item = I1::Item>> Iterator for JoinedMachineIter
     }
 }
 
-pub struct JoinedMachine<T, M1, M2> {
-    t: PhantomData<T>,
+///
+/// Machine running a first machine until it produces an output, which is
+///  turned by `f` into the machine to run next.
+///
+/// If the first machine finishes without an output, the chain ends there.
+///
+#[derive(Clone)]
+pub struct JoinedMachine<S, X, M1, M2, F> {
+    t: PhantomData<(S, X)>,
     state: JoinedMachineState<M1, M2>,
+    stopped: bool,
+    f: F,
 }
 
-impl<T, M2, M1> Machine<Option<T>> for JoinedMachine<T, M1, M2>
+impl<S, X, M1, M2, F> JoinedMachine<S, X, M1, M2, F>
 where
-    M1: Machine<Option<M2>, Transition = M2::Transition>,
-    M2: Machine<Option<T>>,
+    S: Final,
+    M1: Machine<S>,
+    M2: Machine<S::Chained<X>>,
+    F: FnMut(S::Output) -> M2,
+{
+    fn new(machine: M1, f: F) -> JoinedMachine<S, X, M1, M2, F> {
+        JoinedMachine {
+            t: PhantomData,
+            state: JoinedMachineState::First(machine),
+            stopped: false,
+            f,
+        }
+    }
+
+    // Move on to the second machine once the first one has an output,
+    //  returning the state of the machine otherwise.
+    fn advance(&mut self) -> Option<S::Chained<X>> {
+        let JoinedMachineState::First(m1) = &mut self.state else {
+            return None;
+        };
+
+        let state = m1.state();
+        if !state.is_final() {
+            return state.resolve().err();
+        }
+
+        match state.resolve() {
+            Ok(output) => {
+                self.state = JoinedMachineState::Second((self.f)(output));
+                None
+            }
+            Err(state) => {
+                self.stopped = true;
+                Some(state)
+            }
+        }
+    }
+}
+
+impl<S, X, M1, M2, F> Machine<S::Chained<X>> for JoinedMachine<S, X, M1, M2, F>
+where
+    S: Final,
+    M1: Machine<S>,
+    M2: Machine<S::Chained<X>, Transition = M1::Transition>,
+    F: FnMut(S::Output) -> M2,
 {
     type Transition = M2::Transition;
 
     fn edges(&self) -> impl Iterator<Item = Self::Transition> {
-        match &self.state {
+        let edges = match &self.state {
             JoinedMachineState::First(i1) => JoinedMachineIterator::First(i1.edges()),
             JoinedMachineState::Second(i2) => JoinedMachineIterator::Second(i2.edges()),
-        }
+        };
+
+        (!self.stopped).then_some(edges).into_iter().flatten()
     }
 
-    // fn state(&self) -> &Option<T> {
-    //     match &self.state {
-    //         JoinedMachineState::First(_) => &None,
-    //         JoinedMachineState::Second(m2) => m2.state(),
-    //     }
-    // }
+    fn state(&mut self) -> S::Chained<X> {
+        if let Some(state) = self.advance() {
+            return state;
+        }
 
-    fn state(&mut self) -> Option<T> {
         match &mut self.state {
-            JoinedMachineState::First(_) => None,
+            JoinedMachineState::First(_) => unreachable!("advanced to the second machine"),
             JoinedMachineState::Second(m2) => m2.state(),
         }
     }
 
     fn traverse(&mut self, edge: &Self::Transition) {
         match &mut self.state {
+            _ if self.stopped => return,
             JoinedMachineState::First(m1) => m1.traverse(edge),
             JoinedMachineState::Second(m2) => m2.traverse(edge),
         };
 
         // Try to get the second machine from the first machine's state
-        match &mut self.state {
-            JoinedMachineState::First(m1) => {
-                if let Some(m2) = m1.state() {
-                    self.state = JoinedMachineState::Second(m2);
-                }
-            }
-            _ => {}
-        };
+        self.advance();
     }
 }
 
-pub struct ChainedMachine<T, M1, M2> {
-    t: PhantomData<T>,
+#[derive(Clone)]
+pub struct ChainedMachine<T, U, M1, M2> {
+    t: PhantomData<(T, U)>,
     in_second_machine: bool,
+    stopped: bool,
     machine1: M1,
     machine2: M2,
 }
@@ -134,53 +334,84 @@ impl<T, I1: Iterator<Item = T>, I2: Iterator<Item = T>> Iterator
     }
 }
 
-impl<T, U, M1: Machine<Option<T>>, M2: Machine<Option<U>, Transition = M1::Transition>>
-    Machine<Option<U>> for ChainedMachine<T, M1, M2>
+impl<T, U, M1, M2> ChainedMachine<T, U, M1, M2>
+where
+    T: Final,
+    M1: Machine<T>,
+{
+    // Move on once the first machine is final, either to the second
+    //  machine or, without an output, to the end of the chain, returning
+    //  the state of the first machine until then.
+    fn advance(&mut self) -> Option<T::Chained<U>> {
+        if self.in_second_machine {
+            return None;
+        }
+
+        let state = self.machine1.state();
+        if !state.is_final() {
+            return state.resolve().err();
+        }
+
+        match state.resolve() {
+            Ok(_) => {
+                self.in_second_machine = true;
+                None
+            }
+            Err(state) => {
+                self.stopped = true;
+                Some(state)
+            }
+        }
+    }
+}
+
+impl<T, U, M1, M2> Machine<T::Chained<U>> for ChainedMachine<T, U, M1, M2>
+where
+    T: Final,
+    M1: Machine<T>,
+    M2: Machine<T::Chained<U>, Transition = M1::Transition>,
 {
     type Transition = M2::Transition;
 
     fn edges(&self) -> impl Iterator<Item = M2::Transition> {
-        ChainedMachineIterator {
+        let edges = ChainedMachineIterator {
             in_second_machine: self.in_second_machine,
             iterator1: self.machine1.edges(),
             iterator2: self.machine2.edges(),
-        }
+        };
+
+        (!self.stopped).then_some(edges).into_iter().flatten()
     }
 
-    fn state(&mut self) -> Option<U> {
-        if self.in_second_machine {
-            self.machine2.state()
-        } else {
-            None
+    fn state(&mut self) -> T::Chained<U> {
+        match self.advance() {
+            Some(state) => state,
+            None => self.machine2.state(),
         }
     }
 
     fn traverse(&mut self, edge: &Self::Transition) {
         if self.in_second_machine {
             self.machine2.traverse(edge);
-        } else {
+        } else if !self.stopped {
             self.machine1.traverse(edge);
-
-            let new_state = self.machine1.state();
-
-            if let Some(_) = new_state {
-                self.in_second_machine = true;
-            }
+            self.advance();
         }
     }
 }
 
-impl<T, M1: Machine<Option<T>>> Chainable<Option<T>> for M1 {
-    type Result<X> = Option<X>;
+impl<T: Final, M1: Machine<T>> Chainable<T> for M1 {
+    type Result<X> = T::Chained<X>;
 
     #[inline]
-    fn chain<U, M2>(self, next: M2) -> impl Machine<Option<U>, Transition = M1::Transition>
+    fn chain<U, M2>(self, next: M2) -> impl Machine<T::Chained<U>, Transition = M1::Transition>
     where
-        M2: Machine<Option<U>, Transition = Self::Transition>,
+        M2: Machine<T::Chained<U>, Transition = Self::Transition>,
     {
         ChainedMachine {
-            t: PhantomData,
+            t: PhantomData::<(T, U)>,
             in_second_machine: false,
+            stopped: false,
             machine1: self,
             machine2: next,
         }
@@ -191,14 +422,24 @@ impl<T, M1: Machine<Option<T>>, M2: Machine<Option<M1>, Transition = M1::Transit
     JoinMachine<M1, Option<T>> for M2
 {
     #[inline]
+    #[allow(refining_impl_trait)]
     fn join(self) -> impl Machine<Option<T>, Transition = M2::Transition>
     where
         Self: Machine<Option<M1>>,
     {
-        JoinedMachine {
-            t: PhantomData,
-            state: JoinedMachineState::First::<M2, M1>(self),
-        }
+        JoinedMachine::new(self, |machine| machine)
+    }
+}
+
+impl<S, X, M> JoinFinal<S, X> for M
+where
+    S: Final<Output: Machine<S::Chained<X>, Transition = M::Transition>>,
+    M: Machine<S>,
+{
+    #[inline]
+    #[allow(refining_impl_trait)]
+    fn join_final(self) -> impl Machine<S::Chained<X>, Transition = M::Transition> {
+        JoinedMachine::new(self, |machine| machine)
     }
 }
 
@@ -210,6 +451,7 @@ pub fn pure<T: Clone, E>(value: T) -> impl Machine<T, Transition = E> {
     }
 }
 
+#[derive(Clone)]
 pub struct PureMachine<T, E> {
     e: PhantomData<E>,
     value: T,
@@ -228,3 +470,168 @@ impl<T: Clone, E> Machine<T> for PureMachine<T, E> {
 
     fn traverse(&mut self, _edge: &Self::Transition) {}
 }
+
+///
+/// Run a machine produced by `step` from an accumulator, again and again,
+///  until one of them finishes with `Break`.
+///
+/// Every machine finishes with either `Continue` of the next accumulator,
+///  in which case a fresh machine is started from it, or `Break` of the
+///  result of the loop. The accumulator is exposed while looping.
+///
+#[inline]
+pub fn loop_with<A, R, M, F>(
+    initial: A,
+    step: F,
+) -> impl Machine<Progress<A, R>, Transition = M::Transition>
+where
+    A: Clone,
+    R: Clone,
+    M: Machine<Option<ControlFlow<R, A>>>,
+    F: FnMut(&A) -> M,
+{
+    LoopMachine::new(initial, step, |_, flow| flow)
+}
+
+///
+/// Restart a machine made by `factory` every time it finishes, collecting
+///  the results until one of them satisfies `done`.
+///
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn repeat_until<T, M, F, P>(
+    mut factory: F,
+    mut done: P,
+) -> impl Machine<Progress<Vec<T>, Vec<T>>, Transition = M::Transition>
+where
+    T: Clone,
+    M: Machine<Option<T>>,
+    F: FnMut() -> M,
+    P: FnMut(&T) -> bool,
+{
+    LoopMachine::new(
+        Vec::new(),
+        move |_: &Vec<T>| factory(),
+        move |mut results: Vec<T>, result| {
+            let finished = done(&result);
+            results.push(result);
+
+            if finished {
+                ControlFlow::Break(results)
+            } else {
+                ControlFlow::Continue(results)
+            }
+        },
+    )
+}
+
+///
+/// Restart a machine made by `factory` every time it finishes, forever,
+///  collecting the results.
+///
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn repeat<T, M, F>(
+    factory: F,
+) -> impl Machine<Progress<Vec<T>, Vec<T>>, Transition = M::Transition>
+where
+    T: Clone,
+    M: Machine<Option<T>>,
+    F: FnMut() -> M,
+{
+    repeat_until(factory, |_| false)
+}
+
+///
+/// Machine restarting a chainable machine whenever it finishes, folding
+///  its results into an accumulator, see [loop_with].
+///
+/// A fresh machine which is already finished is only folded on the next
+///  traversal.
+///
+#[derive(Clone)]
+pub struct LoopMachine<T, A, R, M, F, G> {
+    t: PhantomData<T>,
+    accumulator: Option<A>,
+    result: Option<R>,
+    machine: M,
+    step: F,
+    fold: G,
+}
+
+impl<T, A, R, M, F, G> LoopMachine<T, A, R, M, F, G>
+where
+    M: Machine<Option<T>>,
+    F: FnMut(&A) -> M,
+    G: FnMut(A, T) -> ControlFlow<R, A>,
+{
+    pub fn new(initial: A, mut step: F, fold: G) -> LoopMachine<T, A, R, M, F, G> {
+        let mut machine = LoopMachine {
+            t: PhantomData,
+            machine: step(&initial),
+            accumulator: Some(initial),
+            result: None,
+            step,
+            fold,
+        };
+        machine.advance();
+        machine
+    }
+
+    // Fold the output of the machine once it finishes, and start a fresh
+    //  one. This happens at most once per traversal, so that machines
+    //  which finish straight away cannot make the loop spin forever.
+    fn advance(&mut self) {
+        if self.result.is_some() {
+            return;
+        }
+
+        let Some(output) = self.machine.state() else {
+            return;
+        };
+
+        let accumulator = self.accumulator.take().expect("loop is running");
+        match (self.fold)(accumulator, output) {
+            ControlFlow::Continue(accumulator) => {
+                self.machine = (self.step)(&accumulator);
+                self.accumulator = Some(accumulator);
+            }
+            ControlFlow::Break(result) => self.result = Some(result),
+        }
+    }
+}
+
+impl<T, A, R, M, F, G> Machine<Progress<A, R>> for LoopMachine<T, A, R, M, F, G>
+where
+    A: Clone,
+    R: Clone,
+    M: Machine<Option<T>>,
+    F: FnMut(&A) -> M,
+    G: FnMut(A, T) -> ControlFlow<R, A>,
+{
+    type Transition = M::Transition;
+
+    fn edges(&self) -> impl Iterator<Item = M::Transition> {
+        let machine = match self.result {
+            Some(_) => None,
+            None => Some(&self.machine),
+        };
+
+        machine.into_iter().flat_map(|machine| machine.edges())
+    }
+
+    fn state(&mut self) -> Progress<A, R> {
+        match (&self.result, &self.accumulator) {
+            (Some(result), _) => Progress::Done(result.clone()),
+            (None, Some(accumulator)) => Progress::InProgress(accumulator.clone()),
+            (None, None) => unreachable!("loop is running"),
+        }
+    }
+
+    fn traverse(&mut self, edge: &M::Transition) {
+        if self.result.is_none() {
+            self.machine.traverse(edge);
+            self.advance();
+        }
+    }
+}
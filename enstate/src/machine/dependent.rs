@@ -0,0 +1,77 @@
+use core::marker::PhantomData;
+
+use super::Machine;
+
+///
+/// Machine running a second machine made from the state of the first,
+///  see [Machine::zip_dependent].
+///
+#[derive(Clone)]
+pub struct DependentMachine<T, U, M1, M2, G, F> {
+    pub(crate) u: PhantomData<U>,
+    pub(crate) key: T,
+    pub(crate) machine1: M1,
+    // Only empty while it is being remade.
+    pub(crate) machine2: Option<M2>,
+    pub(crate) g: G,
+    pub(crate) f: F,
+}
+
+impl<T, U, M1, M2, G, F> DependentMachine<T, U, M1, M2, G, F>
+where
+    M1: Machine<T>,
+    G: FnMut(&T, Option<M2>) -> M2,
+{
+    pub fn new(mut machine1: M1, mut g: G, f: F) -> DependentMachine<T, U, M1, M2, G, F> {
+        let key = machine1.state();
+
+        DependentMachine {
+            u: PhantomData,
+            machine2: Some(g(&key, None)),
+            key,
+            machine1,
+            g,
+            f,
+        }
+    }
+}
+
+impl<T, U, V, M1, M2, G, F> Machine<V> for DependentMachine<T, U, M1, M2, G, F>
+where
+    T: Clone + PartialEq,
+    M1: Machine<T>,
+    M2: Machine<U, Transition = M1::Transition>,
+    G: FnMut(&T, Option<M2>) -> M2,
+    F: FnMut(T, U) -> V,
+{
+    type Transition = M1::Transition;
+
+    fn edges(&self) -> impl Iterator<Item = M1::Transition> {
+        self.machine1
+            .edges()
+            .chain(self.machine2.iter().flat_map(|machine2| machine2.edges()))
+    }
+
+    fn state(&mut self) -> V {
+        let state2 = self
+            .machine2
+            .as_mut()
+            .expect("dependent machine is present")
+            .state();
+
+        (self.f)(self.key.clone(), state2)
+    }
+
+    fn traverse(&mut self, edge: &M1::Transition) {
+        self.machine1.traverse(edge);
+        if let Some(machine2) = &mut self.machine2 {
+            machine2.traverse(edge);
+        }
+
+        let key = self.machine1.state();
+        if key != self.key {
+            self.machine2 = Some((self.g)(&key, self.machine2.take()));
+            self.key = key;
+        }
+    }
+}
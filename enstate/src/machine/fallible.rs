@@ -0,0 +1,189 @@
+use core::marker::PhantomData;
+
+use super::Machine;
+use super::chained::{Final, JoinedMachineIterator, JoinedMachineState};
+
+///
+/// State of a machine which may be restarted after failing, along with
+///  the attempt it is on, starting at `1`.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Attempt<S> {
+    pub attempt: usize,
+    pub state: S,
+}
+
+impl<S: Final> Final for Attempt<S> {
+    type Output = S::Output;
+    type Chained<X> = Attempt<S::Chained<X>>;
+
+    fn is_final(&self) -> bool {
+        self.state.is_final()
+    }
+
+    fn resolve<X>(self) -> Result<S::Output, Attempt<S::Chained<X>>> {
+        let attempt = self.attempt;
+        self.state
+            .resolve()
+            .map_err(|state| Attempt { attempt, state })
+    }
+}
+
+///
+/// Run the machine made by `factory`, starting it over whenever it
+///  finishes with an error, at most `retries` times.
+///
+/// The error of the last attempt is kept once no retries are left.
+///
+#[inline]
+pub fn retry<T, E, M, F>(retries: usize, factory: F) -> RetryMachine<T, E, M, F>
+where
+    M: Machine<Result<Option<T>, E>>,
+    F: FnMut() -> M,
+{
+    RetryMachine::new(retries, factory)
+}
+
+///
+/// Run a machine, switching to the fallback made by `f` from its error
+///  if it fails.
+///
+/// The fallback counts as the second attempt.
+///
+#[inline]
+pub fn or_else<T, E, E2, M1, M2, F>(machine: M1, f: F) -> OrElseMachine<T, E, M1, M2, F>
+where
+    M1: Machine<Result<Option<T>, E>>,
+    M2: Machine<Result<Option<T>, E2>, Transition = M1::Transition>,
+    F: FnMut(E) -> M2,
+{
+    OrElseMachine::new(machine, f)
+}
+
+#[derive(Clone)]
+pub struct RetryMachine<T, E, M, F> {
+    t: PhantomData<(T, E)>,
+    retries: usize,
+    attempt: usize,
+    machine: M,
+    factory: F,
+}
+
+impl<T, E, M, F> RetryMachine<T, E, M, F>
+where
+    M: Machine<Result<Option<T>, E>>,
+    F: FnMut() -> M,
+{
+    pub fn new(retries: usize, mut factory: F) -> RetryMachine<T, E, M, F> {
+        let mut machine = RetryMachine {
+            t: PhantomData,
+            retries,
+            attempt: 1,
+            machine: factory(),
+            factory,
+        };
+        machine.advance();
+        machine
+    }
+
+    fn advance(&mut self) {
+        while self.attempt <= self.retries && self.machine.state().is_err() {
+            self.attempt += 1;
+            self.machine = (self.factory)();
+        }
+    }
+}
+
+impl<T, E, M, F> Machine<Attempt<Result<Option<T>, E>>> for RetryMachine<T, E, M, F>
+where
+    M: Machine<Result<Option<T>, E>>,
+    F: FnMut() -> M,
+{
+    type Transition = M::Transition;
+
+    fn edges(&self) -> impl Iterator<Item = M::Transition> {
+        self.machine.edges()
+    }
+
+    fn state(&mut self) -> Attempt<Result<Option<T>, E>> {
+        Attempt {
+            attempt: self.attempt,
+            state: self.machine.state(),
+        }
+    }
+
+    fn traverse(&mut self, edge: &M::Transition) {
+        self.machine.traverse(edge);
+        self.advance();
+    }
+}
+
+#[derive(Clone)]
+pub struct OrElseMachine<T, E, M1, M2, F> {
+    t: PhantomData<(T, E)>,
+    state: JoinedMachineState<M1, M2>,
+    f: F,
+}
+
+impl<T, E, M1, M2, F> OrElseMachine<T, E, M1, M2, F>
+where
+    M1: Machine<Result<Option<T>, E>>,
+    F: FnMut(E) -> M2,
+{
+    pub fn new(machine: M1, f: F) -> OrElseMachine<T, E, M1, M2, F> {
+        let mut machine = OrElseMachine {
+            t: PhantomData,
+            state: JoinedMachineState::First(machine),
+            f,
+        };
+        machine.advance();
+        machine
+    }
+
+    fn advance(&mut self) {
+        if let JoinedMachineState::First(m1) = &mut self.state
+            && let Err(error) = m1.state()
+        {
+            self.state = JoinedMachineState::Second((self.f)(error));
+        }
+    }
+}
+
+impl<T, E, E2, M1, M2, F> Machine<Attempt<Result<Option<T>, E2>>> for OrElseMachine<T, E, M1, M2, F>
+where
+    M1: Machine<Result<Option<T>, E>>,
+    M2: Machine<Result<Option<T>, E2>, Transition = M1::Transition>,
+    F: FnMut(E) -> M2,
+{
+    type Transition = M1::Transition;
+
+    fn edges(&self) -> impl Iterator<Item = M1::Transition> {
+        match &self.state {
+            JoinedMachineState::First(m1) => JoinedMachineIterator::First(m1.edges()),
+            JoinedMachineState::Second(m2) => JoinedMachineIterator::Second(m2.edges()),
+        }
+    }
+
+    fn state(&mut self) -> Attempt<Result<Option<T>, E2>> {
+        match &mut self.state {
+            JoinedMachineState::First(m1) => Attempt {
+                attempt: 1,
+                state: Ok(m1
+                    .state()
+                    .unwrap_or_else(|_| unreachable!("switched to fallback"))),
+            },
+            JoinedMachineState::Second(m2) => Attempt {
+                attempt: 2,
+                state: m2.state(),
+            },
+        }
+    }
+
+    fn traverse(&mut self, edge: &M1::Transition) {
+        match &mut self.state {
+            JoinedMachineState::First(m1) => m1.traverse(edge),
+            JoinedMachineState::Second(m2) => m2.traverse(edge),
+        }
+        self.advance();
+    }
+}
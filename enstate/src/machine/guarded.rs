@@ -0,0 +1,42 @@
+use super::Machine;
+
+///
+/// Machine only offering the edges of another machine which are allowed
+///  by a predicate on its state, see [Machine::guard].
+///
+/// The state is cached for the predicate, and refreshed whenever it is
+///  read and after every traversal.
+///
+#[derive(Clone)]
+pub struct GuardedMachine<T, M, F> {
+    pub(crate) state: T,
+    pub(crate) machine: M,
+    pub(crate) f: F,
+}
+
+impl<T, M, F> Machine<T> for GuardedMachine<T, M, F>
+where
+    T: Clone,
+    M: Machine<T>,
+    F: Fn(&T, &M::Transition) -> bool,
+{
+    type Transition = M::Transition;
+
+    fn edges(&self) -> impl Iterator<Item = M::Transition> {
+        self.machine
+            .edges()
+            .filter(move |edge| (self.f)(&self.state, edge))
+    }
+
+    fn state(&mut self) -> T {
+        self.state = self.machine.state();
+        self.state.clone()
+    }
+
+    fn traverse(&mut self, edge: &M::Transition) {
+        if (self.f)(&self.state, edge) {
+            self.machine.traverse(edge);
+            self.state = self.machine.state();
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use core::marker::PhantomData;
+
+use super::Machine;
+
+///
+/// Allow a chainable machine to be interrupted by any of `interrupts`,
+///  which are offered alongside its own edges until it finishes.
+///
+/// Taking an interrupt finishes the machine straight away with the
+///  result of `on_interrupt`. Interrupts take precedence over edges of
+///  the machine which compare equal to them.
+///
+#[inline]
+pub fn interruptible<T, M, I, F>(
+    machine: M,
+    interrupts: I,
+    on_interrupt: F,
+) -> InterruptibleMachine<T, M, I, F>
+where
+    M: Machine<Option<T>>,
+    I: IntoIterator<Item = M::Transition> + Clone,
+    F: FnMut(&M::Transition) -> T,
+{
+    InterruptibleMachine::new(machine, interrupts, on_interrupt)
+}
+
+#[derive(Clone)]
+pub struct InterruptibleMachine<T, M, I, F> {
+    t: PhantomData<T>,
+    machine: M,
+    interrupts: I,
+    on_interrupt: F,
+    finished: bool,
+    interrupted: Option<T>,
+}
+
+impl<T, M, I, F> InterruptibleMachine<T, M, I, F>
+where
+    M: Machine<Option<T>>,
+    I: IntoIterator<Item = M::Transition> + Clone,
+    F: FnMut(&M::Transition) -> T,
+{
+    pub fn new(mut machine: M, interrupts: I, on_interrupt: F) -> InterruptibleMachine<T, M, I, F> {
+        InterruptibleMachine {
+            t: PhantomData,
+            finished: machine.state().is_some(),
+            machine,
+            interrupts,
+            on_interrupt,
+            interrupted: None,
+        }
+    }
+
+    fn is_interrupt(&self, edge: &M::Transition) -> bool
+    where
+        M::Transition: PartialEq,
+    {
+        self.interrupts.clone().into_iter().any(|i| i == *edge)
+    }
+}
+
+impl<T, M, I, F> Machine<Option<T>> for InterruptibleMachine<T, M, I, F>
+where
+    T: Clone,
+    M: Machine<Option<T>, Transition: PartialEq>,
+    I: IntoIterator<Item = M::Transition> + Clone,
+    F: FnMut(&M::Transition) -> T,
+{
+    type Transition = M::Transition;
+
+    fn edges(&self) -> impl Iterator<Item = M::Transition> {
+        let running = !self.finished;
+        let interrupts = running.then(|| self.interrupts.clone());
+        let machine = self.interrupted.is_none().then(|| self.machine.edges());
+
+        machine
+            .into_iter()
+            .flatten()
+            .filter(move |edge| !running || !self.is_interrupt(edge))
+            .chain(interrupts.into_iter().flatten())
+    }
+
+    fn state(&mut self) -> Option<T> {
+        match &self.interrupted {
+            Some(result) => Some(result.clone()),
+            None => self.machine.state(),
+        }
+    }
+
+    fn traverse(&mut self, edge: &M::Transition) {
+        if self.interrupted.is_some() {
+            return;
+        }
+
+        if !self.finished && self.is_interrupt(edge) {
+            self.interrupted = Some((self.on_interrupt)(edge));
+            self.finished = true;
+        } else {
+            self.machine.traverse(edge);
+            self.finished = self.machine.state().is_some();
+        }
+    }
+}
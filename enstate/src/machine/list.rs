@@ -0,0 +1,110 @@
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+
+use super::Machine;
+
+///
+/// Transitions of a [MachineList], either routed to the machine with the
+///  given key or changing which machines are in the list.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ListTransition<K, E> {
+    Item(K, E),
+    /// Add a machine made by the factory of the list at the end, unless
+    ///  the key is already in use.
+    Insert(K),
+    Remove(K),
+}
+
+///
+/// A keyed collection of machines of the same type, which can grow and
+///  shrink while it is running.
+///
+/// The state lists the state of every machine in the order they were
+///  inserted. Every key is offered for removal, but since keys for new
+///  machines cannot be enumerated, inserts are accepted without being
+///  offered as edges.
+///
+#[derive(Clone)]
+pub struct MachineList<T, K, M, F> {
+    t: PhantomData<T>,
+    machines: Vec<(K, M)>,
+    factory: F,
+}
+
+impl<T, K, M, F> MachineList<T, K, M, F>
+where
+    K: PartialEq,
+    M: Machine<T>,
+    F: FnMut(&K) -> M,
+{
+    pub fn new(factory: F) -> MachineList<T, K, M, F> {
+        MachineList {
+            t: PhantomData,
+            machines: Vec::new(),
+            factory,
+        }
+    }
+
+    ///
+    /// Start with a machine for each of `keys`.
+    ///
+    pub fn with_keys(mut self, keys: impl IntoIterator<Item = K>) -> Self {
+        for key in keys {
+            self.insert(key);
+        }
+        self
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.machines.iter().map(|(key, _)| key)
+    }
+
+    fn insert(&mut self, key: K) {
+        if !self.machines.iter().any(|(k, _)| *k == key) {
+            let machine = (self.factory)(&key);
+            self.machines.push((key, machine));
+        }
+    }
+}
+
+impl<T, K, M, F> Machine<Vec<(K, T)>> for MachineList<T, K, M, F>
+where
+    K: Clone + PartialEq,
+    M: Machine<T>,
+    M::Transition: Clone,
+    F: FnMut(&K) -> M,
+{
+    type Transition = ListTransition<K, M::Transition>;
+
+    fn edges(&self) -> impl Iterator<Item = Self::Transition> {
+        let items = self.machines.iter().flat_map(|(key, machine)| {
+            machine
+                .edges()
+                .map(move |edge| ListTransition::Item(key.clone(), edge))
+        });
+        let removals = self.keys().map(|key| ListTransition::Remove(key.clone()));
+
+        items.chain(removals)
+    }
+
+    fn state(&mut self) -> Vec<(K, T)> {
+        self.machines
+            .iter_mut()
+            .map(|(key, machine)| (key.clone(), machine.state()))
+            .collect()
+    }
+
+    fn traverse(&mut self, edge: &Self::Transition) {
+        match edge {
+            ListTransition::Item(key, edge) => {
+                if let Some((_, machine)) = self.machines.iter_mut().find(|(k, _)| k == key) {
+                    machine.traverse(edge);
+                }
+            }
+            ListTransition::Insert(key) => self.insert(key.clone()),
+            ListTransition::Remove(key) => self.machines.retain(|(k, _)| k != key),
+        }
+    }
+}
@@ -6,6 +6,7 @@ use super::Machine;
 /// MappedMachine allows for mapping Machine transition type into another type while maintaining
 /// the same semantics.
 ///
+#[derive(Clone)]
 pub struct MappedMachine<T, M, F> {
     pub t: PhantomData<T>,
     pub machine: M,
@@ -36,6 +37,7 @@ where
 /// MappedTransitionMachine allows for mapping Machine state type into another type while maintaining
 /// the same semantics.
 ///
+#[derive(Clone)]
 pub struct MappedTransitionMachine<T, M, F, G> {
     pub t: PhantomData<T>,
     pub machine: M,
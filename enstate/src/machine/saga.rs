@@ -0,0 +1,227 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use super::Machine;
+use super::boxed::BoxedMachine;
+
+type Compensation<'a, A> = BoxedMachine<'a, Option<()>, A>;
+type Compensate<'a, T, A> = Box<dyn FnOnce(&T) -> Compensation<'a, A> + 'a>;
+type Step<'a, T, E, A> = (
+    BoxedMachine<'a, Option<Result<T, E>>, A>,
+    Compensate<'a, T, A>,
+);
+
+///
+/// Describes a saga which failed, and the steps that were undone.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RolledBack<E> {
+    /// Index of the step which failed.
+    pub failed_step: usize,
+    pub error: E,
+    /// Indices of the steps which were compensated, in the order their
+    ///  compensations ran.
+    pub compensated: Vec<usize>,
+}
+
+///
+/// Builder for a multi-step workflow, where every step registers a
+///  compensation to undo it should a later step fail.
+///
+/// The steps are chainable machines finishing with a `Result`, and are
+///  run in order while they succeed. Once one of them fails, the
+///  compensations of the steps that succeeded so far are run in reverse
+///  order.
+///
+pub struct Saga<'a, T, E, A> {
+    steps: Vec<Step<'a, T, E, A>>,
+}
+
+impl<'a, T: 'a, E: 'a, A: 'a> Saga<'a, T, E, A> {
+    pub fn new() -> Saga<'a, T, E, A> {
+        Saga { steps: Vec::new() }
+    }
+
+    ///
+    /// Add a step, along with a function making the machine that undoes
+    ///  it from its result.
+    ///
+    pub fn step<M, U, C, F>(mut self, machine: M, compensate: F) -> Self
+    where
+        M: Machine<Option<Result<T, E>>, Transition = A> + 'a,
+        U: 'a,
+        C: Machine<Option<U>, Transition = A> + 'a,
+        F: FnOnce(&T) -> C + 'a,
+    {
+        let compensate = move |result: &T| {
+            compensate(result)
+                .map(|state: Option<U>| state.map(|_| ()))
+                .boxed()
+        };
+
+        self.steps.push((machine.boxed(), Box::new(compensate)));
+        self
+    }
+
+    ///
+    /// Start running the saga.
+    ///
+    pub fn start(self) -> SagaMachine<'a, T, E, A> {
+        let mut steps = self.steps;
+        steps.reverse();
+
+        let mut machine = SagaMachine {
+            steps,
+            current: None,
+            results: Vec::new(),
+            compensations: Vec::new(),
+            phase: Phase::Forward,
+        };
+        machine.advance();
+        machine
+    }
+}
+
+impl<'a, T: 'a, E: 'a, A: 'a> Default for Saga<'a, T, E, A> {
+    fn default() -> Self {
+        Saga::new()
+    }
+}
+
+enum Phase<T, E> {
+    Forward,
+    Compensating {
+        failed_step: usize,
+        error: E,
+        compensated: Vec<usize>,
+    },
+    Done(Result<Vec<T>, RolledBack<E>>),
+}
+
+///
+/// Machine running a [Saga], which is `None` until every step succeeded
+///  or the saga was rolled back.
+///
+pub struct SagaMachine<'a, T, E, A> {
+    // Steps still to run, last one first.
+    steps: Vec<Step<'a, T, E, A>>,
+    current: Option<Step<'a, T, E, A>>,
+    results: Vec<T>,
+    compensations: Vec<(usize, Compensation<'a, A>)>,
+    phase: Phase<T, E>,
+}
+
+impl<T, E, A> SagaMachine<'_, T, E, A> {
+    // Move through steps and compensations for as long as they are done.
+    fn advance(&mut self) {
+        loop {
+            match &mut self.phase {
+                Phase::Forward => {
+                    if self.current.is_none() {
+                        self.current = self.steps.pop();
+                    }
+
+                    let Some((step, _)) = &mut self.current else {
+                        let results = core::mem::take(&mut self.results);
+                        self.phase = Phase::Done(Ok(results));
+                        return;
+                    };
+
+                    match step.state() {
+                        None => return,
+                        Some(Ok(result)) => {
+                            let (_, compensate) = self.current.take().expect("step is running");
+                            self.compensations
+                                .push((self.results.len(), compensate(&result)));
+                            self.results.push(result);
+                        }
+                        Some(Err(error)) => {
+                            self.current = None;
+                            self.phase = Phase::Compensating {
+                                failed_step: self.results.len(),
+                                error,
+                                compensated: Vec::new(),
+                            };
+                        }
+                    }
+                }
+                Phase::Compensating { compensated, .. } => match self.compensations.last_mut() {
+                    Some((step, compensation)) => {
+                        if compensation.state().is_none() {
+                            return;
+                        }
+                        compensated.push(*step);
+                        self.compensations.pop();
+                    }
+                    None => {
+                        let Phase::Compensating {
+                            failed_step,
+                            error,
+                            compensated,
+                        } = core::mem::replace(&mut self.phase, Phase::Forward)
+                        else {
+                            unreachable!("saga is compensating")
+                        };
+
+                        self.phase = Phase::Done(Err(RolledBack {
+                            failed_step,
+                            error,
+                            compensated,
+                        }));
+                        return;
+                    }
+                },
+                Phase::Done(_) => return,
+            }
+        }
+    }
+}
+
+impl<T: Clone, E: Clone, A> Machine<Option<Result<Vec<T>, RolledBack<E>>>>
+    for SagaMachine<'_, T, E, A>
+{
+    type Transition = A;
+
+    fn edges(&self) -> impl Iterator<Item = A> {
+        let edges: Vec<A> = match &self.phase {
+            Phase::Forward => self
+                .current
+                .iter()
+                .flat_map(|(step, _)| step.edges())
+                .collect(),
+            Phase::Compensating { .. } => self
+                .compensations
+                .last()
+                .into_iter()
+                .flat_map(|(_, compensation)| compensation.edges())
+                .collect(),
+            Phase::Done(_) => Vec::new(),
+        };
+
+        edges.into_iter()
+    }
+
+    fn state(&mut self) -> Option<Result<Vec<T>, RolledBack<E>>> {
+        match &self.phase {
+            Phase::Done(result) => Some(result.clone()),
+            _ => None,
+        }
+    }
+
+    fn traverse(&mut self, edge: &A) {
+        match &self.phase {
+            Phase::Forward => {
+                if let Some((step, _)) = &mut self.current {
+                    step.traverse(edge);
+                }
+            }
+            Phase::Compensating { .. } => {
+                if let Some((_, compensation)) = self.compensations.last_mut() {
+                    compensation.traverse(edge);
+                }
+            }
+            Phase::Done(_) => return,
+        }
+
+        self.advance();
+    }
+}
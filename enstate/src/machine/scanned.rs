@@ -0,0 +1,43 @@
+use core::marker::PhantomData;
+
+use super::Machine;
+
+///
+/// Machine keeping an accumulator alongside another machine, updated
+///  from every edge it accepts, see [Machine::scan].
+///
+#[derive(Clone)]
+pub struct ScannedMachine<T, M, A, F> {
+    pub(crate) t: PhantomData<T>,
+    pub(crate) machine: M,
+    pub(crate) acc: A,
+    pub(crate) f: F,
+}
+
+impl<T, M, A, F> Machine<(T, A)> for ScannedMachine<T, M, A, F>
+where
+    M: Machine<T, Transition: PartialEq>,
+    A: Clone,
+    F: FnMut(&mut A, &M::Transition, &T),
+{
+    type Transition = M::Transition;
+
+    fn edges(&self) -> impl Iterator<Item = M::Transition> {
+        self.machine.edges()
+    }
+
+    fn state(&mut self) -> (T, A) {
+        (self.machine.state(), self.acc.clone())
+    }
+
+    fn traverse(&mut self, edge: &M::Transition) {
+        // Edges which are not offered are no-ops, so they do not count.
+        if !self.machine.edges().any(|e| e == *edge) {
+            return;
+        }
+
+        self.machine.traverse(edge);
+        let state = self.machine.state();
+        (self.f)(&mut self.acc, edge, &state);
+    }
+}
@@ -0,0 +1,67 @@
+use super::Machine;
+
+///
+/// A machine backed by a flat transition table.
+///
+/// States are numbered from `0`, and the edges out of state `i` are
+///  stored in `edges[offsets[i]..offsets[i + 1]]` as pairs of a
+///  transition and the index of the state it leads to.
+///
+/// Since the table is only borrowed, this can be built from `static`
+///  data on targets without an allocator, and is cheap to copy.
+///
+pub struct TableMachine<'a, E, T> {
+    pub states: &'a [T],
+    pub offsets: &'a [usize],
+    pub edges: &'a [(E, usize)],
+    pub current: usize,
+}
+
+impl<'a, E, T> TableMachine<'a, E, T> {
+    pub const fn new(
+        states: &'a [T],
+        offsets: &'a [usize],
+        edges: &'a [(E, usize)],
+        start: usize,
+    ) -> Self {
+        TableMachine {
+            states,
+            offsets,
+            edges,
+            current: start,
+        }
+    }
+
+    ///
+    /// The edges out of the current state, along with their targets.
+    ///
+    pub fn outgoing(&self) -> &'a [(E, usize)] {
+        &self.edges[self.offsets[self.current]..self.offsets[self.current + 1]]
+    }
+}
+
+impl<E, T> Clone for TableMachine<'_, E, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E, T> Copy for TableMachine<'_, E, T> {}
+
+impl<E: Clone + PartialEq, T: Clone> Machine<T> for TableMachine<'_, E, T> {
+    type Transition = E;
+
+    fn edges(&self) -> impl Iterator<Item = E> {
+        self.outgoing().iter().map(|(edge, _)| edge.clone())
+    }
+
+    fn state(&mut self) -> T {
+        self.states[self.current].clone()
+    }
+
+    fn traverse(&mut self, edge: &E) {
+        if let Some((_, target)) = self.outgoing().iter().find(|(e, _)| e == edge) {
+            self.current = *target;
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use core::marker::PhantomData;
+
+use super::Machine;
+
+///
+/// A machine whose edges carry relative weights, describing how likely
+///  each of them is to be taken, as in a Markov chain.
+///
+/// Weights only need to be non-negative; they are normalized over the
+///  edges offered in each state.
+///
+pub trait WeightedMachine<T>: Machine<T> {
+    fn weight(&self, edge: &Self::Transition) -> f64;
+}
+
+///
+/// Machine weighting its edges with a function, see [Machine::weighted].
+///
+#[derive(Clone)]
+pub struct Weighted<T, M, F> {
+    pub(crate) t: PhantomData<T>,
+    pub(crate) machine: M,
+    pub(crate) f: F,
+}
+
+impl<T, M, F> Machine<T> for Weighted<T, M, F>
+where
+    M: Machine<T>,
+{
+    type Transition = M::Transition;
+
+    fn edges(&self) -> impl Iterator<Item = M::Transition> {
+        self.machine.edges()
+    }
+
+    fn state(&mut self) -> T {
+        self.machine.state()
+    }
+
+    fn traverse(&mut self, edge: &M::Transition) {
+        self.machine.traverse(edge);
+    }
+}
+
+impl<T, M, F> WeightedMachine<T> for Weighted<T, M, F>
+where
+    M: Machine<T>,
+    F: Fn(&M::Transition) -> f64,
+{
+    fn weight(&self, edge: &M::Transition) -> f64 {
+        (self.f)(edge)
+    }
+}
+
+///
+/// Drives a weighted machine by sampling its edges, using a small
+///  seeded generator so that simulations can be reproduced.
+///
+#[derive(Clone)]
+pub struct Simulator<T, M> {
+    t: PhantomData<T>,
+    machine: M,
+    seed: u64,
+}
+
+impl<T, M: WeightedMachine<T>> Simulator<T, M> {
+    pub fn new(machine: M, seed: u64) -> Simulator<T, M> {
+        Simulator {
+            t: PhantomData,
+            machine,
+            seed,
+        }
+    }
+
+    pub fn machine(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    pub fn into_inner(self) -> M {
+        self.machine
+    }
+
+    ///
+    /// Sample an edge and traverse it, returning the edge taken, or `None`
+    ///  if the machine offers no edge with a positive weight.
+    ///
+    pub fn step(&mut self) -> Option<M::Transition> {
+        let total: f64 = self
+            .machine
+            .edges()
+            .map(|edge| self.machine.weight(&edge))
+            .filter(|weight| *weight > 0.0)
+            .sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut remaining = self.uniform() * total;
+        let mut chosen = None;
+        for edge in self.machine.edges() {
+            let weight = self.machine.weight(&edge);
+            if weight <= 0.0 {
+                continue;
+            }
+
+            remaining -= weight;
+            chosen = Some(edge);
+            if remaining < 0.0 {
+                break;
+            }
+        }
+
+        let edge = chosen?;
+        self.machine.traverse(&edge);
+        Some(edge)
+    }
+
+    ///
+    /// Step the machine until its state satisfies `done`, returning the
+    ///  number of steps taken, or `None` if that did not happen within
+    ///  `max_steps` steps or the machine got stuck.
+    ///
+    pub fn run_until(
+        &mut self,
+        max_steps: usize,
+        mut done: impl FnMut(&T) -> bool,
+    ) -> Option<usize> {
+        for steps in 0..=max_steps {
+            if done(&self.machine.state()) {
+                return Some(steps);
+            }
+            if steps == max_steps || self.step().is_none() {
+                break;
+            }
+        }
+
+        None
+    }
+
+    // SplitMix64, mapped to a float in [0, 1).
+    fn uniform(&mut self) -> f64 {
+        self.seed = self.seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
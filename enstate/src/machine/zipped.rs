@@ -2,6 +2,15 @@ use core::marker::PhantomData;
 
 use super::Machine;
 
+///
+/// Machine running two machines side by side, offering the edges of both,
+///  where edges offered by both machines are only listed once.
+///
+/// Every traversed edge is passed to both machines, so components are
+///  expected to respond to disjoint sets of edges, for example by mapping
+///  their actions into a shared enum.
+///
+#[derive(Clone)]
 pub struct ZippedMachine<T, U, M1, M2, F> {
     pub(crate) t: PhantomData<T>,
     pub(crate) u: PhantomData<U>,
@@ -12,14 +21,18 @@ pub struct ZippedMachine<T, U, M1, M2, F> {
 
 impl<M1, M2, F, T, U, V> Machine<V> for ZippedMachine<T, U, M1, M2, F>
 where
-    M1: Machine<T>,
+    M1: Machine<T, Transition: PartialEq>,
     M2: Machine<U, Transition = M1::Transition>,
     F: FnMut(T, U) -> V,
 {
     type Transition = M1::Transition;
 
     fn edges(&self) -> impl Iterator<Item = M1::Transition> {
-        self.machine1.edges()
+        let offered = |edge: &M1::Transition| self.machine1.edges().any(|e| e == *edge);
+
+        self.machine1
+            .edges()
+            .chain(self.machine2.edges().filter(move |edge| !offered(edge)))
     }
 
     fn state(&mut self) -> V {
@@ -31,7 +44,7 @@ where
     }
 
     fn traverse(&mut self, edge: &M1::Transition) {
-        self.machine1.traverse(&edge);
-        self.machine2.traverse(&edge);
+        self.machine1.traverse(edge);
+        self.machine2.traverse(edge);
     }
 }
@@ -1,14 +1,43 @@
-#![feature(
-    coroutines,
-    coroutine_trait,
-    trait_alias,
-    never_type,
-    exhaustive_patterns
-)]
+#![feature(coroutines, coroutine_clone, exhaustive_patterns)]
 
 mod examples {
+    mod array;
+    mod basket;
+    // Kept as originally written.
+    #[allow(
+        clippy::assign_op_pattern,
+        clippy::clone_on_copy,
+        clippy::manual_map,
+        clippy::redundant_closure
+    )]
     mod chaining;
+    mod choose;
+    // Kept as originally written.
+    #[allow(clippy::assign_op_pattern, clippy::manual_map)]
     mod counter;
+    mod dependent;
+    mod door;
+    mod equivalence;
+    mod fallible;
+    mod guard;
+    mod interrupt;
+    mod list;
+    mod loader;
+    mod markov;
+    // Kept as originally written.
+    #[allow(clippy::assign_op_pattern, clippy::clone_on_copy, clippy::manual_map)]
     mod modal_dialog;
+    mod payment;
+    mod saga;
+    mod scan;
+    mod scenario;
+    mod selection;
+    mod table;
+    mod trace;
+    // Kept as originally written.
+    #[allow(clippy::assign_op_pattern)]
     mod vending_machine;
+    mod volume;
+    mod wizard;
+    mod zip;
 }
@@ -0,0 +1,67 @@
+use enstate::machine::Machine;
+use enstate::machine::array::{ArrayTransition, array_zip};
+
+use super::volume::{VolumeAction, volume};
+
+#[test]
+fn array_example() {
+    use ArrayTransition::*;
+
+    // A mixing desk with a volume control per channel.
+    let mut machine = array_zip([volume(), volume(), volume()]);
+
+    assert_eq!(machine.state(), [5, 5, 5]);
+    assert_eq!(machine.edges().count(), 12);
+    assert_eq!(
+        machine
+            .edges()
+            .filter(|edge| matches!(edge, Broadcast(_)))
+            .collect::<Vec<_>>(),
+        [
+            Broadcast(VolumeAction::Up),
+            Broadcast(VolumeAction::Down),
+            Broadcast(VolumeAction::ToggleMute)
+        ]
+    );
+
+    machine.traverse(&Item(0, VolumeAction::Up));
+    machine.traverse(&Item(2, VolumeAction::ToggleMute));
+    assert_eq!(machine.state(), [6, 5, 0]);
+
+    machine.traverse(&Broadcast(VolumeAction::Down));
+    assert_eq!(machine.state(), [5, 4, 0]);
+
+    // Out of range indices are ignored.
+    machine.traverse(&Item(3, VolumeAction::Up));
+    assert_eq!(machine.state(), [5, 4, 0]);
+}
+
+#[test]
+fn array_broadcast_example() {
+    use ArrayTransition::*;
+
+    let mut silent = volume();
+    for _ in 0..5 {
+        silent.traverse(&VolumeAction::Down);
+    }
+
+    // Only the channels which can still be turned down are.
+    let mut machine = array_zip([silent, volume()]);
+    assert_eq!(machine.state(), [0, 5]);
+    assert!(
+        !machine
+            .edges()
+            .any(|edge| edge == Item(0, VolumeAction::Down))
+    );
+    assert!(
+        machine
+            .edges()
+            .any(|edge| edge == Broadcast(VolumeAction::Down))
+    );
+
+    machine.traverse(&Broadcast(VolumeAction::Down));
+    assert_eq!(machine.state(), [0, 4]);
+
+    machine.traverse(&Item(0, VolumeAction::Down));
+    assert_eq!(machine.state(), [0, 4]);
+}
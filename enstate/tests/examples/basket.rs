@@ -0,0 +1,71 @@
+use std::ops::ControlFlow;
+
+use enstate::machine::Machine;
+use enstate::machine::chained::{Progress, loop_with, pure, repeat, repeat_until};
+use enstate_macros::machine_chain;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Item {
+    Apple,
+    Pear,
+    Done,
+}
+
+///
+/// Asks for a single item to put in the basket.
+///
+fn ask_item() -> impl Machine<Option<Item>, Transition = Item> {
+    machine_chain!(|| choose![Item::Apple, Item::Pear, Item::Done])
+}
+
+#[test]
+fn basket_example() {
+    let mut machine = repeat_until(ask_item, |item| *item == Item::Done);
+
+    assert_eq!(machine.state(), Progress::InProgress(vec![]));
+
+    machine.traverse(&Item::Apple);
+    machine.traverse(&Item::Pear);
+    assert_eq!(
+        machine.state(),
+        Progress::InProgress(vec![Item::Apple, Item::Pear])
+    );
+
+    machine.traverse(&Item::Done);
+    assert_eq!(
+        machine.state(),
+        Progress::Done(vec![Item::Apple, Item::Pear, Item::Done])
+    );
+    assert_eq!(machine.edges().count(), 0);
+
+    // Count apples until done, keeping the count as the accumulator.
+    let mut machine = loop_with(0, |&apples| {
+        ask_item().map(move |item| {
+            item.map(|item| match item {
+                Item::Apple => ControlFlow::Continue(apples + 1),
+                Item::Pear => ControlFlow::Continue(apples),
+                Item::Done => ControlFlow::Break(apples),
+            })
+        })
+    });
+
+    machine.traverse(&Item::Apple);
+    machine.traverse(&Item::Pear);
+    machine.traverse(&Item::Apple);
+    assert_eq!(machine.state(), Progress::InProgress(2));
+
+    machine.traverse(&Item::Done);
+    assert_eq!(machine.state(), Progress::Done(2));
+}
+
+#[test]
+fn basket_finished_items_example() {
+    // Machines which finish straight away are restarted once per step,
+    //  rather than forever.
+    let mut machine = repeat(|| pure::<_, Item>(Some(1)));
+    assert_eq!(machine.state(), Progress::InProgress(vec![1]));
+    assert_eq!(machine.edges().count(), 0);
+
+    machine.traverse(&Item::Apple);
+    assert_eq!(machine.state(), Progress::InProgress(vec![1, 1]));
+}
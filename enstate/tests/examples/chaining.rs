@@ -1,4 +1,4 @@
-use enstate::machine::chained::{Chainable, pure};
+use enstate::machine::chained::Chainable;
 use enstate::machine::{Machine, chained::FlatMappable};
 
 use crate::examples::{
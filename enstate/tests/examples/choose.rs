@@ -0,0 +1,104 @@
+use enstate::machine::Machine;
+use enstate::machine::chained::Chainable;
+use enstate_macros::{machine, machine_chain};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Answer {
+    Yes,
+    No,
+}
+
+///
+/// Counts the questions answered with yes, choosing in an `if` condition.
+///
+fn survey() -> impl Machine<u32, Transition = Answer> {
+    machine!(yes, 0, || {
+        if choose![Answer::Yes, Answer::No] == Answer::Yes {
+            yes += 1;
+        }
+    })
+}
+
+///
+/// Asks for two answers, choosing inside nested blocks.
+///
+fn confirm() -> impl Chainable<Option<(Answer, Answer)>, Transition = Answer> {
+    machine_chain!(|| {
+        let first = { choose![Answer::Yes, Answer::No] };
+        let second = {
+            let answer = choose![Answer::Yes, Answer::No];
+            assert!(matches!(answer, Answer::Yes | Answer::No));
+            answer
+        };
+        (first, second)
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Page {
+    Back,
+    Next(u32),
+}
+
+///
+/// Pages through a document, offering the number of the next page.
+///
+fn pager() -> impl Machine<u32, Transition = Page> {
+    machine!(page, 1, || {
+        let next = page + 1;
+        match choose![Page::Back, Page::Next(next)] {
+            Page::Back => page = page.saturating_sub(1),
+            Page::Next(n) => page = n,
+        }
+    })
+}
+
+#[test]
+fn choose_in_condition_example() {
+    let mut machine = survey();
+
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [Answer::Yes, Answer::No]
+    );
+
+    machine.traverse(&Answer::Yes);
+    machine.traverse(&Answer::No);
+    machine.traverse(&Answer::Yes);
+    assert_eq!(machine.state(), 2);
+}
+
+#[test]
+fn choose_in_block_example() {
+    let mut machine = confirm();
+
+    assert_eq!(machine.state(), None);
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [Answer::Yes, Answer::No]
+    );
+
+    machine.traverse(&Answer::No);
+    assert_eq!(machine.state(), None);
+
+    machine.traverse(&Answer::Yes);
+    assert_eq!(machine.state(), Some((Answer::No, Answer::Yes)));
+    assert_eq!(machine.edges().count(), 0);
+}
+
+#[test]
+fn choose_runtime_value_example() {
+    let mut machine = pager();
+
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [Page::Back, Page::Next(2)]
+    );
+
+    machine.traverse(&Page::Next(2));
+    assert_eq!(machine.state(), 2);
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [Page::Back, Page::Next(3)]
+    );
+}
@@ -1,5 +1,5 @@
 use enstate::machine::Machine;
-use enstate_macros::machine;
+use enstate_macros::{edge_table, machine};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Action {
@@ -7,12 +7,7 @@ pub enum Action {
     Decrement,
 }
 
-impl Default for Action {
-    fn default() -> Self {
-        Self::Increment
-    }
-}
-
+#[edge_table(COUNTER_EDGES)]
 pub fn counter() -> impl Machine<i32, Transition = Action> {
     machine!(count, 0, || {
         let action = choose![Action::Increment, Action::Decrement];
@@ -32,3 +27,8 @@ fn counter_example() {
     machine.traverse(&Action::Increment);
     assert_eq!(machine.state(), 1);
 }
+
+#[test]
+fn counter_edge_table_example() {
+    assert_eq!(COUNTER_EDGES, [["Action::Increment", "Action::Decrement"]]);
+}
@@ -0,0 +1,80 @@
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+use super::counter::Action;
+use super::selection::{SelectionAction, selection};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StockAction {
+    List(SelectionAction),
+    Item(Action),
+}
+
+fn list_action(action: StockAction) -> Option<SelectionAction> {
+    match action {
+        StockAction::List(action) => Some(action),
+        _ => None,
+    }
+}
+
+fn item_action(action: StockAction) -> Option<Action> {
+    match action {
+        StockAction::Item(action) => Some(action),
+        _ => None,
+    }
+}
+
+///
+/// Editor for the quantity of the selected item, which can only be
+///  changed while an item is selected.
+///
+fn quantity(selected: Option<usize>) -> impl Machine<i32, Transition = StockAction> {
+    let editing = selected.is_some();
+
+    machine!(count, 0, || {
+        let action = choose![Action::Increment if editing, Action::Decrement if editing];
+        match action {
+            Action::Increment => count += 1,
+            Action::Decrement => count -= 1,
+        }
+    })
+    .map_actions(StockAction::Item, item_action)
+}
+
+#[test]
+fn dependent_example() {
+    use StockAction::*;
+
+    let list = || selection(3).map_actions(List, list_action);
+
+    // The editor starts over whenever another item is selected.
+    let mut machine = list().zip_dependent(|selected, _| quantity(*selected), |s, q| (s, q));
+
+    assert_eq!(machine.state(), (None, 0));
+    assert!(!machine.edges().any(|edge| edge == Item(Action::Increment)));
+
+    machine.traverse(&List(SelectionAction::Select(1)));
+    machine.traverse(&Item(Action::Increment));
+    assert_eq!(machine.state(), (Some(1), 1));
+
+    machine.traverse(&List(SelectionAction::Select(2)));
+    assert_eq!(machine.state(), (Some(2), 0));
+
+    // Keeping the editor carries the quantity over, for as long as an item
+    //  stays selected.
+    let mut machine = list().zip_dependent(
+        |selected, previous| match (selected, previous) {
+            (Some(_), Some(previous)) => previous,
+            _ => quantity(*selected),
+        },
+        |s, q| (s, q),
+    );
+
+    machine.traverse(&List(SelectionAction::Select(1)));
+    machine.traverse(&Item(Action::Increment));
+    machine.traverse(&List(SelectionAction::Select(2)));
+    assert_eq!(machine.state(), (Some(2), 1));
+
+    machine.traverse(&List(SelectionAction::Clear));
+    assert_eq!(machine.state(), (None, 0));
+}
@@ -0,0 +1,61 @@
+use enstate::machine::Machine;
+use enstate_macros::Machine;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DoorAction {
+    Open,
+    Close,
+    Lock,
+    Unlock,
+    Force,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Machine)]
+#[machine(transition = DoorAction)]
+pub enum Door {
+    #[on(Open => Opened)]
+    #[on(Lock => Locked { forced: 0 })]
+    Closed,
+    #[on(Close => Closed)]
+    Opened,
+    #[on(Unlock => Closed, guard = not_jammed)]
+    #[on(Force, action = force)]
+    Locked { forced: u32 },
+}
+
+fn not_jammed(door: &Door) -> bool {
+    matches!(door, Door::Locked { forced } if *forced < 2)
+}
+
+fn force(door: &mut Door, _action: &DoorAction) {
+    if let Door::Locked { forced } = door {
+        *forced += 1;
+    }
+}
+
+#[test]
+fn door_example() {
+    let mut door = Door::Closed;
+
+    assert_eq!(
+        door.edges().collect::<Vec<_>>(),
+        [DoorAction::Open, DoorAction::Lock]
+    );
+
+    door.traverse(&DoorAction::Close);
+    assert_eq!(door.state(), Door::Closed);
+
+    door.traverse(&DoorAction::Lock);
+    door.traverse(&DoorAction::Force);
+    assert_eq!(door.state(), Door::Locked { forced: 1 });
+    assert_eq!(
+        door.edges().collect::<Vec<_>>(),
+        [DoorAction::Unlock, DoorAction::Force]
+    );
+
+    door.traverse(&DoorAction::Force);
+    assert_eq!(door.edges().collect::<Vec<_>>(), [DoorAction::Force]);
+
+    door.traverse(&DoorAction::Unlock);
+    assert_eq!(door.state(), Door::Locked { forced: 2 });
+}
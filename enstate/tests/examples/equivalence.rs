@@ -0,0 +1,65 @@
+use enstate::analysis::equivalence::{Counterexample, Divergence, check_equivalence};
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+use super::counter::Action;
+
+///
+/// The hand-written counter from the README.
+///
+#[derive(Clone)]
+struct Counter {
+    count: i32,
+    max: i32,
+}
+
+impl Machine<i32> for Counter {
+    type Transition = Action;
+
+    fn edges(&self) -> impl Iterator<Item = Self::Transition> {
+        [Action::Decrement, Action::Increment].into_iter()
+    }
+
+    fn state(&mut self) -> i32 {
+        self.count
+    }
+
+    fn traverse(&mut self, edge: &Self::Transition) {
+        match edge {
+            Action::Increment => self.count = (self.count + 1).min(self.max),
+            Action::Decrement => self.count -= 1,
+        }
+    }
+}
+
+fn counter() -> impl Machine<i32, Transition = Action> + Clone {
+    machine!(count, 0, || {
+        let action = choose![Action::Increment, Action::Decrement];
+        match action {
+            Action::Increment => count += 1,
+            Action::Decrement => count -= 1,
+        }
+    })
+}
+
+#[test]
+fn equivalence_example() {
+    let unbounded = Counter {
+        count: 0,
+        max: i32::MAX,
+    };
+
+    assert_eq!(check_equivalence(&unbounded, &counter(), 6), Ok(()));
+
+    let bounded = Counter { count: 0, max: 2 };
+
+    assert_eq!(check_equivalence(&bounded, &counter(), 2), Ok(()));
+
+    assert_eq!(
+        check_equivalence(&bounded, &counter(), 4),
+        Err(Counterexample {
+            trace: vec![Action::Increment, Action::Increment, Action::Increment],
+            divergence: Divergence::State { left: 2, right: 3 },
+        })
+    );
+}
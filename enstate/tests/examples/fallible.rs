@@ -0,0 +1,147 @@
+use std::ops::ControlFlow;
+
+use enstate::machine::Machine;
+use enstate::machine::chained::{Chainable, Final, FlatMapFinal, Progress};
+use enstate_macros::{machine, machine_chain};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FormAction {
+    Submit,
+    Cancel,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cancelled;
+
+///
+/// A form which either submits a value, or is cancelled.
+///
+fn form(value: u32) -> impl Machine<Result<Option<u32>, Cancelled>, Transition = FormAction> {
+    machine_chain!(|| {
+        match choose![FormAction::Submit, FormAction::Cancel] {
+            FormAction::Submit => Ok(value),
+            FormAction::Cancel => Err(Cancelled),
+        }
+    })
+    .map(Option::transpose)
+}
+
+///
+/// A form which keeps offering its buttons after being cancelled.
+///
+fn sticky_form() -> impl Machine<Result<Option<u32>, Cancelled>, Transition = FormAction> {
+    machine!(result, Ok(None), || {
+        match choose![FormAction::Submit, FormAction::Cancel] {
+            FormAction::Submit => {}
+            FormAction::Cancel => result = Err(Cancelled),
+        }
+    })
+}
+
+///
+/// Steps of a checkout, finishing with the amount paid.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Checkout<R> {
+    Browsing,
+    Paid(R),
+    Abandoned,
+}
+
+impl<R> Final for Checkout<R> {
+    type Output = R;
+    type Chained<X> = Checkout<X>;
+
+    fn is_final(&self) -> bool {
+        !matches!(self, Checkout::Browsing)
+    }
+
+    fn resolve<X>(self) -> Result<R, Checkout<X>> {
+        match self {
+            Checkout::Browsing => Err(Checkout::Browsing),
+            Checkout::Paid(amount) => Ok(amount),
+            Checkout::Abandoned => Err(Checkout::Abandoned),
+        }
+    }
+}
+
+#[test]
+fn fallible_example() {
+    let mut machine = form(1).chain(form(2));
+
+    machine.traverse(&FormAction::Submit);
+    assert_eq!(machine.state(), Ok(None));
+    machine.traverse(&FormAction::Submit);
+    assert_eq!(machine.state(), Ok(Some(2)));
+
+    // Cancelling the first form skips the second one.
+    let mut machine = form(1).chain(form(2));
+
+    machine.traverse(&FormAction::Cancel);
+    assert_eq!(machine.state(), Err(Cancelled));
+    assert!(machine.state().is_final());
+    assert_eq!(machine.edges().count(), 0);
+
+    let mut machine = form(1)
+        .flat_map_final(|first| form(2).map(move |second| second.map(|s| s.map(|s| first + s))));
+
+    machine.traverse(&FormAction::Submit);
+    machine.traverse(&FormAction::Submit);
+    assert_eq!(machine.state(), Ok(Some(3)));
+
+    // Progress and ControlFlow states are chained the same way.
+    let mut machine = form(1)
+        .map(|result| match result {
+            Ok(Some(value)) => ControlFlow::Break(value),
+            _ => ControlFlow::Continue("filling in"),
+        })
+        .chain(form(2).map(|result| match result {
+            Ok(Some(value)) => ControlFlow::Break(value * 10),
+            _ => ControlFlow::Continue("filling in"),
+        }));
+
+    assert_eq!(machine.state(), ControlFlow::Continue("filling in"));
+    machine.traverse(&FormAction::Submit);
+    machine.traverse(&FormAction::Submit);
+    assert_eq!(machine.state(), ControlFlow::Break(20));
+
+    let checkout = |amount| {
+        form(amount).map(|result| match result {
+            Ok(None) => Checkout::Browsing,
+            Ok(Some(amount)) => Checkout::Paid(amount),
+            Err(Cancelled) => Checkout::Abandoned,
+        })
+    };
+
+    let mut machine = checkout(5).chain(checkout(7));
+    machine.traverse(&FormAction::Submit);
+    assert_eq!(machine.state(), Checkout::Browsing);
+    machine.traverse(&FormAction::Cancel);
+    assert_eq!(machine.state(), Checkout::Abandoned);
+
+    let mut machine = checkout(5).chain(checkout(7));
+    machine.traverse(&FormAction::Cancel);
+    assert_eq!(machine.state(), Checkout::Abandoned::<u32>);
+    assert_eq!(machine.edges().count(), 0);
+
+    assert!(!Progress::<u32, bool>::InProgress(1).is_final());
+}
+
+#[test]
+fn fallible_final_example() {
+    // The chain ends once the first machine is final, even if that
+    //  machine still offers edges.
+    let mut machine = sticky_form().chain(form(2));
+
+    machine.traverse(&FormAction::Cancel);
+    assert_eq!(machine.state(), Err(Cancelled));
+    assert_eq!(machine.edges().count(), 0);
+    machine.traverse(&FormAction::Submit);
+    assert_eq!(machine.state(), Err(Cancelled));
+
+    let mut machine = sticky_form().flat_map_final(|_| form(2));
+
+    machine.traverse(&FormAction::Cancel);
+    assert_eq!(machine.state(), Err(Cancelled));
+    assert_eq!(machine.edges().count(), 0);
+}
@@ -0,0 +1,111 @@
+use core::marker::PhantomData;
+
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FieldAction {
+    Type(char),
+    Clear,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FormAction {
+    Agree,
+    Submit,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SignupAction {
+    Form(FormAction),
+    Field(FieldAction),
+}
+
+impl From<FormAction> for SignupAction {
+    fn from(value: FormAction) -> Self {
+        SignupAction::Form(value)
+    }
+}
+
+impl From<FieldAction> for SignupAction {
+    fn from(value: FieldAction) -> Self {
+        SignupAction::Field(value)
+    }
+}
+
+impl TryInto<FormAction> for SignupAction {
+    type Error = ();
+
+    fn try_into(self) -> Result<FormAction, Self::Error> {
+        match self {
+            SignupAction::Form(action) => Ok(action),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<FieldAction> for SignupAction {
+    type Error = ();
+
+    fn try_into(self) -> Result<FieldAction, Self::Error> {
+        match self {
+            SignupAction::Field(action) => Ok(action),
+            _ => Err(()),
+        }
+    }
+}
+
+///
+/// A text field holding what was typed so far.
+///
+fn field() -> impl Machine<String, Transition = FieldAction> {
+    machine!(text, String::new(), || {
+        match choose![FieldAction::Type('a'), FieldAction::Clear] {
+            FieldAction::Type(c) => text.push(c),
+            FieldAction::Clear => text.clear(),
+        }
+    })
+}
+
+fn form() -> impl Machine<bool, Transition = FormAction> {
+    machine!(agreed, false, || {
+        match choose![FormAction::Agree, FormAction::Submit] {
+            FormAction::Agree => agreed = true,
+            FormAction::Submit => {}
+        }
+    })
+}
+
+fn offers_submit(machine: &impl Machine<(bool, String), Transition = SignupAction>) -> bool {
+    machine
+        .edges()
+        .any(|edge| edge == SignupAction::Form(FormAction::Submit))
+}
+
+#[test]
+fn guard_example() {
+    // Submitting is only enabled once the whole form is valid.
+    let mut machine = form()
+        .zip_with_into(PhantomData, field(), |agreed, text| (agreed, text))
+        .guard(|(agreed, text), edge| match edge {
+            SignupAction::Form(FormAction::Submit) => *agreed && !text.is_empty(),
+            _ => true,
+        });
+
+    assert!(!offers_submit(&machine));
+    assert_eq!(machine.edges().count(), 3);
+
+    machine.traverse(&FormAction::Agree.into());
+    assert!(!offers_submit(&machine));
+
+    machine.traverse(&FieldAction::Type('a').into());
+    assert_eq!(machine.state(), (true, "a".to_string()));
+    assert!(offers_submit(&machine));
+
+    // Rejected edges are not traversed.
+    machine.traverse(&FieldAction::Clear.into());
+    assert!(!offers_submit(&machine));
+    machine.traverse(&FormAction::Submit.into());
+    assert_eq!(machine.state(), (true, String::new()));
+}
+
@@ -0,0 +1,54 @@
+use enstate::machine::Machine;
+use enstate::machine::chained::Chainable;
+use enstate::machine::interruptible::interruptible;
+use enstate_macros::machine_chain;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FormAction {
+    Next,
+    Submit,
+    Escape,
+}
+
+///
+/// A two page form, which knows nothing about being cancelled.
+///
+fn form() -> impl Machine<Option<&'static str>, Transition = FormAction> {
+    machine_chain!(|| {
+        choose![FormAction::Next];
+        choose![FormAction::Submit];
+        "submitted"
+    })
+}
+
+#[test]
+fn interrupt_example() {
+    let mut machine = interruptible(form(), [FormAction::Escape], |_| "cancelled");
+
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [FormAction::Next, FormAction::Escape]
+    );
+
+    machine.traverse(&FormAction::Next);
+    machine.traverse(&FormAction::Escape);
+    assert_eq!(machine.state(), Some("cancelled"));
+    assert_eq!(machine.edges().count(), 0);
+
+    // Escape is no longer offered once the form is submitted.
+    let mut machine = interruptible(form(), [FormAction::Escape], |_| "cancelled");
+
+    machine.traverse(&FormAction::Next);
+    machine.traverse(&FormAction::Submit);
+    machine.traverse(&FormAction::Escape);
+    assert_eq!(machine.state(), Some("submitted"));
+
+    // Any part of a chain can be interrupted by wrapping the whole chain.
+    let mut machine = interruptible(form().chain(form()), [FormAction::Escape], |_| "cancelled");
+
+    machine.traverse(&FormAction::Next);
+    machine.traverse(&FormAction::Submit);
+    machine.traverse(&FormAction::Next);
+    machine.traverse(&FormAction::Escape);
+    assert_eq!(machine.state(), Some("cancelled"));
+}
@@ -0,0 +1,53 @@
+use enstate::machine::Machine;
+use enstate::machine::list::{ListTransition, MachineList};
+use enstate_macros::machine;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TodoAction {
+    Check,
+    Uncheck,
+}
+
+///
+/// A single to-do item, which can be checked off.
+///
+fn todo() -> impl Machine<bool, Transition = TodoAction> {
+    machine!(done, false, || {
+        let action = choose![TodoAction::Check if !done, TodoAction::Uncheck if done];
+        match action {
+            TodoAction::Check => done = true,
+            TodoAction::Uncheck => done = false,
+        }
+    })
+}
+
+#[test]
+fn list_example() {
+    use ListTransition::*;
+
+    let mut machine = MachineList::new(|_: &u32| todo()).with_keys([1, 2]);
+
+    assert_eq!(machine.state(), [(1, false), (2, false)]);
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [
+            Item(1, TodoAction::Check),
+            Item(2, TodoAction::Check),
+            Remove(1),
+            Remove(2)
+        ]
+    );
+
+    machine.traverse(&Item(2, TodoAction::Check));
+    machine.traverse(&Insert(3));
+    assert_eq!(machine.state(), [(1, false), (2, true), (3, false)]);
+
+    // Inserting an existing key keeps the machine already there.
+    machine.traverse(&Insert(2));
+    machine.traverse(&Remove(1));
+    assert_eq!(machine.state(), [(2, true), (3, false)]);
+
+    // Transitions for keys which are not in the list are ignored.
+    machine.traverse(&Item(1, TodoAction::Check));
+    assert_eq!(machine.keys().collect::<Vec<_>>(), [&2, &3]);
+}
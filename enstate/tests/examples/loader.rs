@@ -0,0 +1,135 @@
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, Wake, Waker};
+
+use enstate::coroutines::asynchronous::AsyncState;
+use enstate::machine::Machine;
+use enstate_macros::async_machine;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LoaderAction {
+    Load,
+    Clear,
+}
+
+///
+/// Mock of some I/O, which completes once the test provides a value.
+///
+#[derive(Clone, Default)]
+struct MockIo(Rc<Cell<Option<u32>>>, Rc<RefCell<Option<Waker>>>);
+
+impl MockIo {
+    fn complete(&self, value: u32) {
+        self.0.set(Some(value));
+        if let Some(waker) = self.1.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for MockIo {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        match self.0.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                self.1.replace(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+        }
+    }
+}
+
+///
+/// Waker counting how often it was woken.
+///
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn loader_example() {
+    let io = MockIo::default();
+    let file = io.clone();
+
+    let mut machine = async_machine!(contents, None, || {
+        match choose![LoaderAction::Load, LoaderAction::Clear if contents.is_some()] {
+            LoaderAction::Load => contents = Some(file.clone().await),
+            LoaderAction::Clear => contents = None,
+        }
+    });
+
+    assert_eq!(machine.state(), AsyncState::Ready(None));
+
+    machine.traverse(&LoaderAction::Load);
+    assert_eq!(machine.state(), AsyncState::Pending);
+    assert_eq!(machine.edges().count(), 0);
+
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(machine.poll(&mut cx), Poll::Pending);
+
+    io.complete(42);
+    assert_eq!(machine.poll(&mut cx), Poll::Ready(()));
+    assert_eq!(machine.state(), AsyncState::Ready(Some(42)));
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [LoaderAction::Load, LoaderAction::Clear]
+    );
+
+    machine.traverse(&LoaderAction::Clear);
+    assert_eq!(machine.state(), AsyncState::Ready(None));
+}
+
+#[test]
+fn loader_wakes_example() {
+    let io = MockIo::default();
+    let file = io.clone();
+
+    let mut machine = async_machine!(contents, 0, || {
+        match choose![LoaderAction::Load, LoaderAction::Clear] {
+            LoaderAction::Load => contents = file.clone().await,
+            LoaderAction::Clear => return,
+        }
+    });
+
+    // The waker given to `poll` is used when traversing starts the I/O.
+    let counter = Arc::new(CountingWaker::default());
+    let waker = Waker::from(counter.clone());
+    assert_eq!(
+        machine.poll(&mut Context::from_waker(&waker)),
+        Poll::Ready(())
+    );
+
+    machine.traverse(&LoaderAction::Load);
+    assert_eq!(machine.state(), AsyncState::Pending);
+
+    io.complete(7);
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        machine.poll(&mut Context::from_waker(&waker)),
+        Poll::Ready(())
+    );
+    assert_eq!(machine.state(), AsyncState::Ready(7));
+
+    // Once the body returns, the machine stays finished.
+    machine.traverse(&LoaderAction::Clear);
+    assert_eq!(machine.state(), AsyncState::Finished);
+    assert_eq!(machine.edges().count(), 0);
+    assert_eq!(
+        machine.poll(&mut Context::from_waker(&waker)),
+        Poll::Ready(())
+    );
+
+    machine.traverse(&LoaderAction::Load);
+    assert_eq!(machine.state(), AsyncState::Finished);
+}
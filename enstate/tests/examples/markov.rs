@@ -0,0 +1,150 @@
+use enstate::analysis::markov::{expected_steps_to_final, stationary_distribution};
+use enstate::machine::Machine;
+use enstate::machine::weighted::Simulator;
+use enstate_macros::{machine, machine_chain};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Weather {
+    Sunny,
+    Rainy,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Change {
+    StaySunny,
+    CloudOver,
+    StayRainy,
+    ClearUp,
+}
+
+fn weather() -> impl Machine<Weather, Transition = Change> + Clone {
+    machine!(weather, Weather::Sunny, || {
+        let change = match weather {
+            Weather::Sunny => choose![Change::StaySunny, Change::CloudOver],
+            Weather::Rainy => choose![Change::StayRainy, Change::ClearUp],
+        };
+        weather = match change {
+            Change::StaySunny | Change::ClearUp => Weather::Sunny,
+            Change::CloudOver | Change::StayRainy => Weather::Rainy,
+        };
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Visit {
+    Browse,
+    Leave,
+}
+
+///
+/// A visitor browsing pages until they leave, counting the pages seen.
+///
+fn visitor() -> impl Machine<Option<u32>, Transition = Visit> + Clone {
+    machine_chain!(|| {
+        let mut pages = 1;
+        loop {
+            match choose![Visit::Browse, Visit::Leave] {
+                Visit::Browse => pages += 1,
+                Visit::Leave => return pages,
+            }
+        }
+    })
+}
+
+///
+/// A visitor who can only leave after seeing three pages.
+///
+fn reader() -> impl Machine<Option<u32>, Transition = Visit> + Clone {
+    machine_chain!(|| {
+        let mut pages = 1;
+        loop {
+            match choose![Visit::Browse, Visit::Leave if pages >= 3] {
+                Visit::Browse => pages += 1,
+                Visit::Leave => return pages,
+            }
+        }
+    })
+}
+
+///
+/// Number of pages a visitor needs to browse before they can leave,
+///  which tells apart visits the state and edges do not.
+///
+fn pages_until_leave(
+    machine: &(impl Machine<Option<u32>, Transition = Visit> + Clone),
+) -> Option<u32> {
+    let mut machine = machine.clone();
+    let mut pages = 0;
+    while machine.state().is_none() && !machine.edges().any(|edge| edge == Visit::Leave) {
+        machine.traverse(&Visit::Browse);
+        pages += 1;
+    }
+    machine.state().is_none().then_some(pages)
+}
+
+#[test]
+fn markov_example() {
+    let weather = weather().weighted(|change| match change {
+        Change::StaySunny => 0.9,
+        Change::CloudOver => 0.1,
+        Change::StayRainy | Change::ClearUp => 0.5,
+    });
+
+    let distribution = stationary_distribution(&weather, |m| m.state(), 10).unwrap();
+    assert_eq!(distribution[0].0, Weather::Sunny);
+    assert!((distribution[0].1 - 5.0 / 6.0).abs() < 1e-9);
+    assert!((distribution[1].1 - 1.0 / 6.0).abs() < 1e-9);
+
+    let visitor = visitor().weighted(|visit| match visit {
+        Visit::Browse => 3.0,
+        Visit::Leave => 1.0,
+    });
+
+    // The page count only matters for the result, so visits which differ
+    //  in nothing else are merged.
+    let expected = expected_steps_to_final(
+        &visitor,
+        |m| (m.state(), m.edges().collect::<Vec<_>>()),
+        1000,
+    )
+    .unwrap();
+    assert!((expected - 4.0).abs() < 1e-9);
+
+    let runs = 2000;
+    let total: usize = (0..runs)
+        .map(|seed| {
+            Simulator::new(visitor.clone(), seed)
+                .run_until(1000, Option::is_some)
+                .unwrap()
+        })
+        .sum();
+    assert!((total as f64 / runs as f64 - 4.0).abs() < 0.3);
+
+    let mut simulator = Simulator::new(visitor, 42);
+    let steps = simulator.run_until(1000, Option::is_some).unwrap();
+    assert_eq!(simulator.machine().state(), Some(steps as u32));
+}
+
+#[test]
+fn markov_hidden_state_example() {
+    let reader = reader().weighted(|visit| match visit {
+        Visit::Browse => 3.0,
+        Visit::Leave => 1.0,
+    });
+
+    // The first pages only differ in the hidden page count, which the key
+    //  needs to see for them not to be merged.
+    let expected = expected_steps_to_final(
+        &reader,
+        |m| {
+            (
+                m.state(),
+                m.edges().collect::<Vec<_>>(),
+                pages_until_leave(m),
+            )
+        },
+        1000,
+    )
+    .unwrap();
+    assert!((expected - 6.0).abs() < 1e-9);
+}
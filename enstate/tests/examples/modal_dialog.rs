@@ -1,5 +1,6 @@
 use std::marker::PhantomData;
 
+use enstate::coroutines::{choices::Choices, resumed};
 use enstate::machine::Machine;
 use enstate::machine::chained::Chainable;
 use enstate_macros::machine_chain;
@@ -60,12 +61,6 @@ impl TryInto<ModalAction> for CountDialogAction {
     }
 }
 
-impl Default for ModalAction {
-    fn default() -> Self {
-        ModalAction::Ok
-    }
-}
-
 ///
 /// This is a generic machine that can be combined (applicatively)
 ///  with other machines to construct the state machine for a generic modal
@@ -77,7 +72,7 @@ impl Default for ModalAction {
 pub fn modal<T: Clone>() -> impl Chainable<Option<fn(T) -> ModalResult<T>>, Transition = ModalAction>
 {
     machine_chain!(|| {
-        let action = yield [ModalAction::Ok, ModalAction::Cancel].as_slice();
+        let action = resumed(yield Choices::Static(&[ModalAction::Ok, ModalAction::Cancel]));
 
         match action {
             ModalAction::Ok => |state| ModalResult::Ok(state),
@@ -86,6 +81,15 @@ pub fn modal<T: Clone>() -> impl Chainable<Option<fn(T) -> ModalResult<T>>, Tran
     })
 }
 
+///
+/// The modal dialog around a counter, shared by the examples driving it.
+///
+pub fn dialog() -> impl Machine<Option<ModalResult<i32>>, Transition = CountDialogAction> {
+    modal().zip_with_into(PhantomData, counter(), |dialog_state, count| {
+        dialog_state.map(|f| f(count))
+    })
+}
+
 #[test]
 fn modal_dialog_example() {
     let contents = counter();
@@ -0,0 +1,85 @@
+use enstate::machine::Machine;
+use enstate::machine::fallible::{Attempt, or_else, retry};
+use enstate_macros::machine_chain;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PaymentAction {
+    Confirm,
+    Fail,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Declined {
+    Card,
+    Cash,
+}
+
+///
+/// A payment which is confirmed, or declined with the given reason.
+///
+fn payment(
+    amount: u32,
+    reason: Declined,
+) -> impl Machine<Result<Option<u32>, Declined>, Transition = PaymentAction> {
+    machine_chain!(|| {
+        match choose![PaymentAction::Confirm, PaymentAction::Fail] {
+            PaymentAction::Confirm => Ok(amount),
+            PaymentAction::Fail => Err(reason),
+        }
+    })
+    .map(Option::transpose)
+}
+
+#[test]
+fn payment_example() {
+    let mut machine = retry(2, || payment(10, Declined::Card));
+
+    assert_eq!(
+        machine.state(),
+        Attempt {
+            attempt: 1,
+            state: Ok(None)
+        }
+    );
+
+    machine.traverse(&PaymentAction::Fail);
+    machine.traverse(&PaymentAction::Fail);
+    assert_eq!(
+        machine.state(),
+        Attempt {
+            attempt: 3,
+            state: Ok(None)
+        }
+    );
+
+    machine.traverse(&PaymentAction::Fail);
+    assert_eq!(
+        machine.state(),
+        Attempt {
+            attempt: 3,
+            state: Err(Declined::Card)
+        }
+    );
+    assert_eq!(machine.edges().count(), 0);
+
+    // Falling back to paying cash when the card is declined.
+    let mut machine = or_else(payment(10, Declined::Card), |_| payment(10, Declined::Cash));
+
+    machine.traverse(&PaymentAction::Fail);
+    assert_eq!(
+        machine.state(),
+        Attempt {
+            attempt: 2,
+            state: Ok(None)
+        }
+    );
+
+    machine.traverse(&PaymentAction::Confirm);
+    assert_eq!(
+        machine.state(),
+        Attempt {
+            attempt: 2,
+            state: Ok(Some(10))
+        }
+    );
+}
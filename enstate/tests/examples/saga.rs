@@ -0,0 +1,71 @@
+use enstate::machine::Machine;
+use enstate::machine::saga::{RolledBack, Saga};
+use enstate_macros::machine_chain;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CheckoutAction {
+    Succeed,
+    Fail,
+    Undo,
+}
+
+///
+/// A checkout step, which either succeeds with a reference number or
+///  fails with the name of the step.
+///
+fn step(
+    name: &'static str,
+    reference: u32,
+) -> impl Machine<Option<Result<u32, &'static str>>, Transition = CheckoutAction> {
+    machine_chain!(|| {
+        match choose![CheckoutAction::Succeed, CheckoutAction::Fail] {
+            CheckoutAction::Succeed => Ok(reference),
+            CheckoutAction::Fail => Err(name),
+            _ => unreachable!(),
+        }
+    })
+}
+
+fn undo() -> impl Machine<Option<CheckoutAction>, Transition = CheckoutAction> {
+    machine_chain!(|| choose![CheckoutAction::Undo])
+}
+
+fn checkout<'a>() -> Saga<'a, u32, &'static str, CheckoutAction> {
+    Saga::new()
+        .step(step("reserve", 1), |_| undo())
+        .step(step("charge", 2), |_| undo())
+        .step(step("ship", 3), |_| undo())
+}
+
+#[test]
+fn saga_example() {
+    let mut machine = checkout().start();
+
+    machine.traverse(&CheckoutAction::Succeed);
+    machine.traverse(&CheckoutAction::Succeed);
+    assert_eq!(machine.state(), None);
+
+    machine.traverse(&CheckoutAction::Succeed);
+    assert_eq!(machine.state(), Some(Ok(vec![1, 2, 3])));
+
+    // Shipping fails, so the charge and reservation are undone.
+    let mut machine = checkout().start();
+
+    machine.traverse(&CheckoutAction::Succeed);
+    machine.traverse(&CheckoutAction::Succeed);
+    machine.traverse(&CheckoutAction::Fail);
+    assert_eq!(machine.state(), None);
+    assert_eq!(machine.edges().collect::<Vec<_>>(), [CheckoutAction::Undo]);
+
+    machine.traverse(&CheckoutAction::Undo);
+    machine.traverse(&CheckoutAction::Undo);
+    assert_eq!(
+        machine.state(),
+        Some(Err(RolledBack {
+            failed_step: 2,
+            error: "ship",
+            compensated: vec![1, 0],
+        }))
+    );
+    assert_eq!(machine.edges().count(), 0);
+}
@@ -0,0 +1,30 @@
+use enstate::machine::Machine;
+
+use super::volume::{VolumeAction, volume};
+
+#[test]
+fn scan_example() {
+    // Count the presses on the volume buttons, and whether it was changed.
+    let mut machine = volume()
+        .scan((0, false), |(presses, dirty), _, level| {
+            *presses += 1;
+            *dirty = *level != 5;
+        })
+        .map(|(level, (presses, dirty))| (level, presses, dirty));
+
+    assert_eq!(machine.state(), (5, 0, false));
+
+    machine.traverse(&VolumeAction::Up);
+    machine.traverse(&VolumeAction::Down);
+    assert_eq!(machine.state(), (5, 2, false));
+
+    for _ in 0..6 {
+        machine.traverse(&VolumeAction::Up);
+    }
+
+    // Up is not offered at the top, so the last press does not count.
+    assert_eq!(machine.state(), (10, 7, true));
+
+    machine.traverse(&VolumeAction::ToggleMute);
+    assert_eq!(machine.state(), (0, 8, true));
+}
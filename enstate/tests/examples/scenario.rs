@@ -0,0 +1,30 @@
+use enstate_test::given;
+
+use crate::examples::{
+    counter::Action,
+    modal_dialog::{ModalAction, ModalResult, dialog},
+};
+
+#[test]
+fn scenario_example() {
+    given(dialog())
+        .then_state(None)
+        .on(Action::Increment)
+        .then_edges_contain(ModalAction::Ok)
+        .on(ModalAction::Ok)
+        .then_state(Some(ModalResult::Ok(1)));
+}
+
+#[test]
+#[should_panic(
+    expected = "scenario failed at step 3: expected state Some(Cancelled), found \
+                           Some(Ok(-1))\n  1. on(Display(Decrement))\n  2. on(Buttons(Ok))\n\
+                           > 3. then_state(Some(Cancelled))\nallowed edges: [Display(Increment), \
+                           Display(Decrement)]"
+)]
+fn failing_scenario_example() {
+    given(dialog())
+        .on(Action::Decrement)
+        .on(ModalAction::Ok)
+        .then_state(Some(ModalResult::Cancelled));
+}
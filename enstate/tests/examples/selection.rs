@@ -0,0 +1,49 @@
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SelectionAction {
+    Clear,
+    Select(usize),
+}
+
+///
+/// A list of items, at most one of which can be selected at a time.
+///
+pub fn selection(items: usize) -> impl Machine<Option<usize>, Transition = SelectionAction> {
+    machine!(selected, None, || {
+        let action = choose![
+            ..(0..items)
+                .filter(|i| selected != Some(*i))
+                .map(SelectionAction::Select),
+            SelectionAction::Clear if selected.is_some()
+        ];
+        match action {
+            SelectionAction::Select(i) => selected = Some(i),
+            SelectionAction::Clear => selected = None,
+        }
+    })
+}
+
+#[test]
+fn selection_example() {
+    use SelectionAction::*;
+
+    let mut machine = selection(3);
+
+    assert_eq!(machine.state(), None);
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [Select(0), Select(1), Select(2)]
+    );
+
+    machine.traverse(&Select(1));
+    assert_eq!(machine.state(), Some(1));
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [Select(0), Select(2), Clear]
+    );
+
+    machine.traverse(&Clear);
+    assert_eq!(machine.state(), None);
+}
@@ -0,0 +1,47 @@
+use enstate::analysis::equivalence::check_equivalence;
+use enstate::analysis::table::{compile, compile_by_key};
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+use super::counter::Action;
+
+fn parity() -> impl Machine<bool, Transition = Action> + Clone {
+    machine!(even, true, || {
+        let _ = choose![Action::Increment, Action::Decrement];
+        even = !even;
+    })
+}
+
+#[test]
+fn table_example() {
+    // A counter modulo 4, where we only care about whether the count is even.
+    let counter = machine!(count, 0, || {
+        let action = choose![Action::Increment, Action::Decrement];
+        match action {
+            Action::Increment => count = (count + 1) % 4,
+            Action::Decrement => count = (count + 3) % 4,
+        }
+    })
+    .map(|count: u8| count.is_multiple_of(2));
+
+    let table = compile_by_key(&counter, |m| m.machine.state(), 16).unwrap();
+    assert_eq!(table.len(), 4);
+    assert_eq!(compile_by_key(&counter, |m| m.machine.state(), 3), None);
+    assert_eq!(compile_by_key(&counter, |m| m.machine.state(), 0), None);
+
+    let minimized = table.minimize();
+    assert_eq!(minimized.len(), 2);
+    assert_eq!(minimized.states, vec![true, false]);
+
+    // Behaves the same as the machine it was built from...
+    assert_eq!(check_equivalence(&minimized.machine(), &counter, 8), Ok(()));
+
+    // ...and has the same canonical form as any other equivalent machine.
+    assert_eq!(compile(&parity(), 16).unwrap().minimize(), minimized);
+
+    let mut machine = minimized.machine();
+    machine.traverse(&Action::Decrement);
+    assert!(!machine.state());
+    machine.traverse(&Action::Decrement);
+    assert!(machine.state());
+}
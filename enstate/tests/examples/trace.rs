@@ -0,0 +1,27 @@
+use enstate::analysis::trace::Tracer;
+use enstate::machine::Machine;
+use enstate::machine::chained::Chainable;
+use enstate_test::assert_golden;
+
+use crate::examples::{
+    counter::Action,
+    modal_dialog::{CountDialogAction, ModalAction, dialog},
+};
+
+#[test]
+fn traced_modal_dialog_example() {
+    let mut machine = Tracer::new(dialog().chain(dialog()));
+
+    machine.traverse(&CountDialogAction::Display(Action::Increment));
+    machine.traverse(&CountDialogAction::Buttons(ModalAction::Ok));
+    machine.traverse(&CountDialogAction::Buttons(ModalAction::Ok));
+    machine.traverse(&CountDialogAction::Display(Action::Increment));
+
+    assert_golden(
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/golden/chained_modal_dialog.trace"
+        ),
+        &machine.to_string(),
+    );
+}
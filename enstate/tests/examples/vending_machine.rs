@@ -8,12 +8,6 @@ enum VendingAction {
     ReturnChange,
 }
 
-impl Default for VendingAction {
-    fn default() -> Self {
-        Self::InsertCoin
-    }
-}
-
 fn vending_machine() -> impl Machine<u32, Transition = VendingAction> {
     machine!(coins, 0, || {
         let action = choose![
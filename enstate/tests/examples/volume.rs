@@ -0,0 +1,95 @@
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VolumeAction {
+    Up,
+    Down,
+    ToggleMute,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Volume {
+    level: u8,
+    muted: bool,
+}
+
+///
+/// A volume control, whose state is the volume that is actually heard.
+///
+pub fn volume() -> impl Machine<u8, Transition = VolumeAction> {
+    machine!(level: 5, muted: false, state = if muted { 0 } else { level }, || {
+        let action = choose![
+            VolumeAction::Up if level < 10,
+            VolumeAction::Down if level > 0,
+            VolumeAction::ToggleMute
+        ];
+        match action {
+            VolumeAction::Up => level += 1,
+            VolumeAction::Down => level -= 1,
+            VolumeAction::ToggleMute => muted = !muted,
+        }
+    })
+}
+
+fn volume_struct() -> impl Machine<Volume, Transition = VolumeAction> {
+    machine!(
+        Volume {
+            level: 10,
+            muted: false
+        },
+        || {
+            let action = choose![VolumeAction::Down, VolumeAction::ToggleMute];
+            match action {
+                VolumeAction::Down => level = level.saturating_sub(1),
+                VolumeAction::ToggleMute => muted = !muted,
+                VolumeAction::Up => unreachable!(),
+            }
+        }
+    )
+}
+
+#[test]
+fn volume_example() {
+    let mut machine = volume();
+
+    assert_eq!(machine.state(), 5);
+
+    machine.traverse(&VolumeAction::Up);
+    assert_eq!(machine.state(), 6);
+
+    machine.traverse(&VolumeAction::ToggleMute);
+    assert_eq!(machine.state(), 0);
+
+    machine.traverse(&VolumeAction::Down);
+    machine.traverse(&VolumeAction::ToggleMute);
+    assert_eq!(machine.state(), 5);
+
+    let mut machine = volume_struct();
+
+    machine.traverse(&VolumeAction::ToggleMute);
+    machine.traverse(&VolumeAction::Down);
+    assert_eq!(
+        machine.state(),
+        Volume {
+            level: 9,
+            muted: true
+        }
+    );
+}
+
+#[test]
+fn single_variable_tuple_example() {
+    let mut machine = machine!(level: 5, || {
+        match choose![VolumeAction::Up, VolumeAction::Down] {
+            VolumeAction::Up => level += 1,
+            VolumeAction::Down => level -= 1,
+            VolumeAction::ToggleMute => unreachable!(),
+        }
+    });
+
+    assert_eq!(machine.state(), (5,));
+
+    machine.traverse(&VolumeAction::Up);
+    assert_eq!(machine.state(), (6,));
+}
@@ -0,0 +1,62 @@
+use enstate::machine::Machine;
+use enstate::machine::chained::{Chainable, Progress};
+use enstate_macros::machine_chain;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WizardAction {
+    Next,
+    Back,
+    Cancel,
+}
+
+///
+/// A wizard with a number of steps, which shows which step it is on
+///  until it is finished or cancelled.
+///
+fn wizard(steps: u32) -> impl Machine<Progress<u32, bool>, Transition = WizardAction> {
+    machine_chain!(step, 1, || {
+        loop {
+            match choose![
+                WizardAction::Next,
+                WizardAction::Back if step > 1,
+                WizardAction::Cancel
+            ] {
+                WizardAction::Next if step == steps => return true,
+                WizardAction::Next => step += 1,
+                WizardAction::Back => step -= 1,
+                WizardAction::Cancel => return false,
+            }
+        }
+    })
+}
+
+#[test]
+fn wizard_example() {
+    let mut machine = wizard(3);
+
+    assert_eq!(machine.state(), Progress::InProgress(1));
+
+    machine.traverse(&WizardAction::Next);
+    machine.traverse(&WizardAction::Next);
+    assert_eq!(machine.state(), Progress::InProgress(3));
+
+    machine.traverse(&WizardAction::Back);
+    assert_eq!(machine.state(), Progress::InProgress(2));
+
+    machine.traverse(&WizardAction::Cancel);
+    assert_eq!(machine.state(), Progress::Done(false));
+    assert_eq!(machine.edges().count(), 0);
+
+    // Only finishing the first wizard moves on to the second.
+    let mut machine = wizard(2).chain(wizard(2));
+
+    machine.traverse(&WizardAction::Next);
+    assert_eq!(machine.state(), Progress::InProgress(2));
+
+    machine.traverse(&WizardAction::Next);
+    assert_eq!(machine.state(), Progress::InProgress(1));
+
+    machine.traverse(&WizardAction::Next);
+    machine.traverse(&WizardAction::Next);
+    assert_eq!(machine.state(), Progress::Done(true));
+}
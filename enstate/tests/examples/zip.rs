@@ -0,0 +1,33 @@
+use std::marker::PhantomData;
+
+use enstate::machine::Machine;
+
+use super::counter::{Action, counter};
+use super::modal_dialog::{CountDialogAction, ModalAction, modal};
+
+#[test]
+fn zip_edges_example() {
+    // Both machines respond to the same edges, which are only offered once.
+    let mut machine = counter().zip_with(counter(), |count1, count2| count1 + count2);
+
+    assert_eq!(
+        machine.edges().collect::<Vec<_>>(),
+        [Action::Increment, Action::Decrement]
+    );
+
+    machine.traverse(&Action::Increment);
+    assert_eq!(machine.state(), 2);
+
+    // The edges of the second machine come after those of the first.
+    let machine = modal::<i32>().zip_with_into(PhantomData, counter(), |_, count| count);
+
+    assert_eq!(
+        machine.edges().collect::<Vec<CountDialogAction>>(),
+        [
+            CountDialogAction::Buttons(ModalAction::Ok),
+            CountDialogAction::Buttons(ModalAction::Cancel),
+            CountDialogAction::Display(Action::Increment),
+            CountDialogAction::Display(Action::Decrement)
+        ]
+    );
+}
@@ -0,0 +1,65 @@
+#![feature(coroutines)]
+#![allow(unused)]
+
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Action {
+    Increment,
+    Decrement,
+    Reset,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Other {
+    Increment,
+}
+
+// Warns about `Action::Reset`, which is never offered.
+fn unoffered() -> impl Machine<i32, Transition = Action> {
+    machine!(count, 0, || {
+        match choose![Action::Increment, Action::Decrement] {
+            Action::Increment => count += 1,
+            Action::Decrement => count -= 1,
+            Action::Reset => count = 0,
+        }
+    })
+}
+
+// No warning, since the arm is marked as unreachable.
+fn unreachable() -> impl Machine<i32, Transition = Action> {
+    machine!(count, 0, || {
+        match choose![Action::Increment, Action::Decrement] {
+            Action::Increment => count += 1,
+            Action::Decrement => count -= 1,
+            Action::Reset => unreachable!(),
+        }
+    })
+}
+
+// Warns about `Action::Decrement`, which is only handled by `_`.
+fn catch_all() -> impl Machine<i32, Transition = Action> {
+    machine!(count, 0, || {
+        match choose![Action::Increment, Action::Decrement] {
+            Action::Increment => count += 1,
+            _ => count = 0,
+        }
+    })
+}
+
+// Warns about `Other::Increment` rather than taking it for `Action::Increment`,
+// besides the type error.
+fn other_enum() -> impl Machine<i32, Transition = Action> {
+    machine!(count, 0, || {
+        let action = choose![Action::Increment];
+        match action {
+            Action::Increment => count += 1,
+            Other::Increment => count -= 1,
+        }
+    })
+}
+
+compile_error!("the warnings above are what is being tested");
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![feature(coroutines)]
+
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+fn counter() -> impl Machine<i32, Transition = bool> {
+    machine!(count, 0, || {
+        let next = async { choose![true, false] };
+        drop(next);
+        count += 1;
+    })
+}
+
+fn main() {
+    counter();
+}
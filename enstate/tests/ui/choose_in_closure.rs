@@ -0,0 +1,17 @@
+#![feature(coroutines)]
+
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+fn counter() -> impl Machine<i32, Transition = bool> {
+    machine!(count, 0, || {
+        let next = || choose![true, false];
+        if next() {
+            count += 1;
+        }
+    })
+}
+
+fn main() {
+    counter();
+}
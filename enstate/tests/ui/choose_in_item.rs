@@ -0,0 +1,20 @@
+#![feature(coroutines)]
+#![allow(unused)]
+
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+fn counter() -> impl Machine<i32, Transition = bool> {
+    machine!(count, 0, || {
+        fn next() -> bool {
+            choose![true, false]
+        }
+        if next() {
+            count += 1;
+        }
+    })
+}
+
+fn main() {
+    counter();
+}
@@ -0,0 +1,20 @@
+#![feature(coroutines)]
+
+use enstate::machine::Machine;
+use enstate_macros::machine;
+
+#[derive(Clone, Default)]
+struct Volume {
+    level: u8,
+    muted: bool,
+}
+
+fn volume() -> impl Machine<Volume, Transition = bool> {
+    machine!(Volume { level: 5, ..Default::default() }, || {
+        muted = choose![true, false];
+    })
+}
+
+fn main() {
+    volume();
+}